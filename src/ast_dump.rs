@@ -0,0 +1,273 @@
+use crate::expr::Expr;
+use crate::object::Object;
+use crate::stmt::{Pattern, Stmt};
+use crate::token::Token;
+
+const INDENT: &str = "  ";
+
+pub fn dump_tokens(tokens: &[Token]) -> String {
+    let mut out = String::new();
+    for token in tokens {
+        out.push_str(&format!(
+            "[line {}] {:?} '{}'\n",
+            token.line, token.ttype, token.lexeme
+        ));
+    }
+    out
+}
+
+pub fn dump_stmts(stmts: &[Stmt]) -> String {
+    let mut dumper = AstDumper::new();
+    for stmt in stmts {
+        dumper.stmt(stmt);
+    }
+    dumper.out
+}
+
+// Writes one node per line, with children indented below their parent.
+struct AstDumper {
+    out: String,
+    level: usize,
+}
+
+impl AstDumper {
+    fn new() -> Self {
+        AstDumper {
+            out: String::new(),
+            level: 0,
+        }
+    }
+
+    fn line(&mut self, s: &str) {
+        for _ in 0..self.level {
+            self.out.push_str(INDENT);
+        }
+        self.out.push_str(s);
+        self.out.push('\n');
+    }
+
+    fn nested(&mut self, label: &str, f: impl FnOnce(&mut Self)) {
+        self.line(label);
+        self.level += 1;
+        f(self);
+        self.level -= 1;
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) {
+        use Stmt::*;
+        match stmt {
+            BlankLine => self.line("BlankLine"),
+            Block { stmts } => self.nested("Block", |d| {
+                for stmt in stmts {
+                    d.stmt(stmt);
+                }
+            }),
+            Comment { text, .. } => self.line(&format!("Comment {}", text)),
+            Verbatim { text } => self.line(&format!("Verbatim {:?}", text)),
+            Expr { expr } => self.nested("Expr", |d| d.expr(expr)),
+            For {
+                init,
+                condition,
+                inc,
+                block,
+            } => self.nested("For", |d| {
+                if let Some(init) = init {
+                    d.nested("init:", |d| d.stmt(init));
+                }
+                if let Some(condition) = condition {
+                    d.nested("condition:", |d| d.expr(condition));
+                }
+                if let Some(inc) = inc {
+                    d.nested("inc:", |d| d.expr(inc));
+                }
+                d.nested("body:", |d| d.stmt(block));
+            }),
+            ForIn {
+                variable,
+                iterable,
+                block,
+            } => self.nested(&format!("ForIn {}", variable.lexeme), |d| {
+                d.nested("iterable:", |d| d.expr(iterable));
+                d.nested("body:", |d| d.stmt(block));
+            }),
+            Fun {
+                name,
+                params,
+                body,
+                depth,
+            } => {
+                let label = format!("Fun {} {}", name.lexeme, depth_label(depth));
+                self.nested(&label, |d| {
+                    let params: Vec<_> = params.iter().map(|p| p.lexeme.clone()).collect();
+                    d.line(&format!("params: ({})", params.join(", ")));
+                    d.nested("body:", |d| {
+                        for stmt in body.iter() {
+                            d.stmt(stmt);
+                        }
+                    });
+                })
+            }
+            If {
+                conditionals,
+                else_block,
+            } => self.nested("If", |d| {
+                for (condition, block) in conditionals {
+                    d.nested("condition:", |d| d.expr(condition));
+                    d.nested("then:", |d| d.stmt(block));
+                }
+                if let Some(block) = else_block {
+                    d.nested("else:", |d| d.stmt(block));
+                }
+            }),
+            Import {
+                path,
+                alias,
+                names,
+                depth,
+                ..
+            } => self.nested(&format!("Import {:?}", path), |d| {
+                for name in alias.iter().chain(names) {
+                    d.line(&format!("{} {}", name.lexeme, depth_label(depth)));
+                }
+            }),
+            Match { value, arms, .. } => self.nested("Match", |d| {
+                d.nested("value:", |d| d.expr(value));
+                for arm in arms {
+                    d.nested("arm:", |d| {
+                        match &arm.pattern {
+                            Pattern::Binding(name) => d.line(&format!("Binding {}", name.lexeme)),
+                            Pattern::Literals(literals) => d.nested("Literals", |d| {
+                                for literal in literals {
+                                    d.expr(literal);
+                                }
+                            }),
+                            Pattern::Wildcard => d.line("Wildcard"),
+                        }
+                        if let Some(guard) = &arm.guard {
+                            d.nested("guard:", |d| d.expr(guard));
+                        }
+                        d.nested("body:", |d| d.stmt(&arm.body));
+                    });
+                }
+            }),
+            Print { expr } => self.nested("Print", |d| d.expr(expr)),
+            Return { expr, .. } => self.nested("Return", |d| d.expr(expr)),
+            VarDecl { variable, value } => self.nested("VarDecl", |d| {
+                d.expr(variable);
+                d.nested("value:", |d| d.expr(value));
+            }),
+            While { condition, block } => self.nested("While", |d| {
+                d.nested("condition:", |d| d.expr(condition));
+                d.nested("body:", |d| d.stmt(block));
+            }),
+        }
+    }
+
+    fn expr(&mut self, expr: &Expr) {
+        use Expr::*;
+        match expr {
+            Assign { variable, value } => self.nested("Assign", |d| {
+                d.expr(variable);
+                d.expr(value);
+            }),
+            Binary {
+                left,
+                operator,
+                right,
+            } => self.nested(&format!("Binary {}", operator.lexeme), |d| {
+                d.expr(left);
+                d.expr(right);
+            }),
+            Call { callee, args, .. } => self.nested("Call", |d| {
+                d.expr(callee);
+                if !args.is_empty() {
+                    d.nested("args:", |d| {
+                        for arg in args.iter() {
+                            d.expr(arg);
+                        }
+                    });
+                }
+            }),
+            CompoundAssign {
+                variable,
+                operator,
+                value,
+            } => self.nested(&format!("CompoundAssign {}", operator.lexeme), |d| {
+                d.expr(variable);
+                d.expr(value);
+            }),
+            Conditional {
+                condition,
+                then_branch,
+                else_branch,
+            } => self.nested("Conditional", |d| {
+                d.nested("condition:", |d| d.expr(condition));
+                d.nested("then:", |d| d.expr(then_branch));
+                d.nested("else:", |d| d.expr(else_branch));
+            }),
+            Get { object, name } => {
+                self.nested(&format!("Get {}", name.lexeme), |d| d.expr(object))
+            }
+            Grouping { expr } => self.nested("Grouping", |d| d.expr(expr)),
+            Increment {
+                variable,
+                operator,
+                postfix,
+            } => {
+                let fixity = if *postfix { "postfix" } else { "prefix" };
+                let label = format!("Increment {} ({})", operator.lexeme, fixity);
+                self.nested(&label, |d| d.expr(variable))
+            }
+            Index { object, index, .. } => self.nested("Index", |d| {
+                d.expr(object);
+                d.expr(index);
+            }),
+            Interpolation { parts } => self.nested("Interpolation", |d| {
+                for part in parts {
+                    d.expr(part);
+                }
+            }),
+            Literal { value, .. } => self.line(&format!("Literal {}", literal_label(value))),
+            Logic {
+                left,
+                operator,
+                right,
+            } => self.nested(&format!("Logic {}", operator.lexeme), |d| {
+                d.expr(left);
+                d.expr(right);
+            }),
+            Range {
+                start,
+                operator,
+                end,
+                step,
+            } => self.nested(&format!("Range {}", operator.lexeme), |d| {
+                d.expr(start);
+                d.expr(end);
+                if let Some(step) = step {
+                    d.nested("step:", |d| d.expr(step));
+                }
+            }),
+            Unary { operator, right } => {
+                self.nested(&format!("Unary {}", operator.lexeme), |d| d.expr(right))
+            }
+            Variable { name, depth } => {
+                self.line(&format!("Variable {} {}", name.lexeme, depth_label(depth)))
+            }
+        }
+    }
+}
+
+fn depth_label(depth: &Option<u32>) -> String {
+    match depth {
+        Some(d) => format!("(depth {})", d),
+        None => "(global)".to_string(),
+    }
+}
+
+fn literal_label(value: &Object) -> String {
+    match value {
+        Object::Str(s) => format!("{:?}", s),
+        _ => value.to_string(),
+    }
+}
@@ -1,14 +1,46 @@
 use crate::callable::Callable;
-use crate::interpreter::Interpreter;
+use crate::files::files;
+use crate::format::{format, MAX_WIDTH};
+use crate::interpreter::{range_item, range_len, Interpreter};
+use crate::json::{self, Json};
 use crate::lox_error::EvalError;
 use crate::object::Object;
+use crate::strings::ItemsFn;
+use crate::system::system;
+use crate::time::time;
 
+use rand::seq::SliceRandom;
 use rand::Rng;
 use std::io;
 use std::io::Write;
 use std::rc::Rc;
 use std::time::{SystemTime, UNIX_EPOCH};
 
+// The natives declared in every global environment.
+pub fn builtins() -> Vec<(&'static str, Rc<dyn Callable>)> {
+    let mut natives: Vec<(&'static str, Rc<dyn Callable>)> = vec![
+        ("arity", Rc::new(ArityFn {})),
+        ("choice", Rc::new(ChoiceFn {})),
+        ("clock", Rc::new(ClockFn {})),
+        ("format", Rc::new(FormatFn {})),
+        ("input", Rc::new(InputFn {})),
+        ("is_callable", Rc::new(IsCallableFn {})),
+        ("json_parse", Rc::new(JsonParseFn {})),
+        ("json_stringify", Rc::new(JsonStringifyFn {})),
+        ("name", Rc::new(NameFn {})),
+        ("num", Rc::new(NumFn {})),
+        ("rand_int", Rc::new(RandIntFn {})),
+        ("random", Rc::new(RandomFn {})),
+        ("shuffle", Rc::new(ShuffleFn {})),
+        ("str", Rc::new(StrFn {})),
+        ("type", Rc::new(TypeFn {})),
+    ];
+    natives.extend(files());
+    natives.extend(system());
+    natives.extend(time());
+    natives
+}
+
 #[derive(Debug)]
 pub struct ClockFn {}
 
@@ -17,6 +49,10 @@ impl Callable for ClockFn {
         0
     }
 
+    fn name(&self) -> Option<&str> {
+        Some("clock")
+    }
+
     fn call(
         &self,
         _interpreter: &mut Interpreter,
@@ -39,6 +75,10 @@ impl Callable for StrFn {
         1
     }
 
+    fn name(&self) -> Option<&str> {
+        Some("str")
+    }
+
     fn call(
         &self,
         _interpreter: &mut Interpreter,
@@ -50,6 +90,35 @@ impl Callable for StrFn {
     }
 }
 
+#[derive(Debug)]
+pub struct FormatFn {}
+
+impl Callable for FormatFn {
+    fn arity(&self) -> usize {
+        1
+    }
+
+    // The template is followed by one argument for each placeholder.
+    fn max_arity(&self) -> usize {
+        usize::MAX
+    }
+
+    fn name(&self) -> Option<&str> {
+        Some("format")
+    }
+
+    fn call(
+        &self,
+        _interpreter: &mut Interpreter,
+        args: &[Rc<Object>],
+    ) -> Result<Rc<Object>, EvalError> {
+        let Object::Str(template) = &*args[0] else {
+            return Err(EvalError::new("Expect format string."));
+        };
+        Ok(Rc::new(Object::Str(format(template, &args[1..])?)))
+    }
+}
+
 #[derive(Debug)]
 pub struct NumFn {}
 
@@ -58,6 +127,10 @@ impl Callable for NumFn {
         1
     }
 
+    fn name(&self) -> Option<&str> {
+        Some("num")
+    }
+
     fn call(
         &self,
         _interpreter: &mut Interpreter,
@@ -79,6 +152,10 @@ impl Callable for InputFn {
         1
     }
 
+    fn name(&self) -> Option<&str> {
+        Some("input")
+    }
+
     fn call(
         &self,
         _interpreter: &mut Interpreter,
@@ -105,16 +182,312 @@ impl Callable for RandIntFn {
         2
     }
 
+    fn name(&self) -> Option<&str> {
+        Some("rand_int")
+    }
+
+    fn call(
+        &self,
+        interpreter: &mut Interpreter,
+        args: &[Rc<Object>],
+    ) -> Result<Rc<Object>, EvalError> {
+        let (low, high) = (int_bound(&args[0])?, int_bound(&args[1])?);
+        if low > high {
+            return Err(EvalError::new("Low bound can't be above high bound."));
+        }
+        let res = interpreter.rng().gen_range(low..=high);
+        Ok(Rc::new(Object::Num(res as f64)))
+    }
+}
+
+fn int_bound(obj: &Object) -> Result<i64, EvalError> {
+    match obj {
+        Object::Num(n) if n.fract() == 0.0 && (i64::MIN as f64..i64::MAX as f64).contains(n) => {
+            Ok(*n as i64)
+        }
+        _ => Err(EvalError::new("Bounds must be integers.")),
+    }
+}
+
+#[derive(Debug)]
+pub struct RandomFn {}
+
+impl Callable for RandomFn {
+    fn arity(&self) -> usize {
+        0
+    }
+
+    fn name(&self) -> Option<&str> {
+        Some("random")
+    }
+
+    // A float in [0, 1).
+    fn call(
+        &self,
+        interpreter: &mut Interpreter,
+        _args: &[Rc<Object>],
+    ) -> Result<Rc<Object>, EvalError> {
+        Ok(Rc::new(Object::Num(interpreter.rng().gen())))
+    }
+}
+
+#[derive(Debug)]
+pub struct ChoiceFn {}
+
+impl Callable for ChoiceFn {
+    fn arity(&self) -> usize {
+        1
+    }
+
+    fn name(&self) -> Option<&str> {
+        Some("choice")
+    }
+
+    // Picks one of the items a for-in loop over the argument would go through.
+    fn call(
+        &self,
+        interpreter: &mut Interpreter,
+        args: &[Rc<Object>],
+    ) -> Result<Rc<Object>, EvalError> {
+        // Ranges are indexed directly rather than gathered into a list.
+        if let Object::Range { .. } = &*args[0] {
+            let Some(len) = range_len(&args[0]) else {
+                return Err(EvalError::new("Can't choose from a range this long."));
+            };
+            if len == 0 {
+                return Err(EvalError::new("Can't choose from an empty sequence."));
+            }
+            let i = interpreter.rng().gen_range(0..len);
+            return Ok(Rc::new(Object::Num(range_item(&args[0], i).unwrap())));
+        }
+        let items = interpreter.collect_items(args[0].clone())?;
+        match items.choose(interpreter.rng()) {
+            Some(item) => Ok(item.clone()),
+            None => Err(EvalError::new("Can't choose from an empty sequence.")),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ShuffleFn {}
+
+impl Callable for ShuffleFn {
+    fn arity(&self) -> usize {
+        1
+    }
+
+    fn name(&self) -> Option<&str> {
+        Some("shuffle")
+    }
+
+    // An iterator function over the argument's items in random order.
+    fn call(
+        &self,
+        interpreter: &mut Interpreter,
+        args: &[Rc<Object>],
+    ) -> Result<Rc<Object>, EvalError> {
+        let mut items = interpreter.collect_items(args[0].clone())?;
+        items.shuffle(interpreter.rng());
+        Ok(Rc::new(Object::Fun(Rc::new(ItemsFn::new(items)))))
+    }
+}
+
+#[derive(Debug)]
+pub struct JsonParseFn {}
+
+impl Callable for JsonParseFn {
+    fn arity(&self) -> usize {
+        1
+    }
+
+    fn name(&self) -> Option<&str> {
+        Some("json_parse")
+    }
+
     fn call(
         &self,
         _interpreter: &mut Interpreter,
         args: &[Rc<Object>],
     ) -> Result<Rc<Object>, EvalError> {
-        let mut rng = rand::thread_rng();
-        let (Object::Num(low), Object::Num(high)) = (&*args[0], &*args[1]) else {
-            return Err(EvalError::new("Expect numbers."));
+        let Object::Str(text) = &*args[0] else {
+            return Err(EvalError::new("Expect string."));
         };
-        let res = rng.gen_range(*low as i32..*high as i32 + 1);
-        Ok(Rc::new(Object::Num(res as f64)))
+        match json::parse(text) {
+            Ok(json) => Ok(Rc::new(from_json(json)?)),
+            Err(err) => Err(EvalError::new(&format!("Invalid JSON: {}.", err))),
+        }
+    }
+}
+
+// Lox has no lists or maps yet, so only scalar JSON values can be parsed.
+fn from_json(json: Json) -> Result<Object, EvalError> {
+    match json {
+        Json::Null => Ok(Object::Nil),
+        Json::Bool(b) => Ok(Object::Bool(b)),
+        Json::Num(n) => Ok(Object::Num(n)),
+        Json::Str(s) => Ok(Object::Str(s)),
+        Json::Array(_) | Json::Object(_) => Err(EvalError::new(
+            "JSON arrays and objects have no Lox value yet.",
+        )),
+    }
+}
+
+#[derive(Debug)]
+pub struct JsonStringifyFn {}
+
+impl Callable for JsonStringifyFn {
+    fn arity(&self) -> usize {
+        1
+    }
+
+    fn name(&self) -> Option<&str> {
+        Some("json_stringify")
+    }
+
+    // The optional second argument is the indent for pretty printing.
+    fn max_arity(&self) -> usize {
+        2
+    }
+
+    fn call(
+        &self,
+        interpreter: &mut Interpreter,
+        args: &[Rc<Object>],
+    ) -> Result<Rc<Object>, EvalError> {
+        let indent = match args.get(1).map(|arg| &**arg) {
+            None | Some(Object::Nil) => 0,
+            Some(Object::Num(n)) if n.fract() == 0.0 && *n >= 0.0 && *n <= MAX_WIDTH as f64 => {
+                *n as usize
+            }
+            Some(_) => {
+                return Err(EvalError::new(&format!(
+                    "Indent must be an integer from 0 to {}.",
+                    MAX_WIDTH
+                )))
+            }
+        };
+        let json = to_json(interpreter, &args[0])?;
+        Ok(Rc::new(Object::Str(json.pretty(indent))))
+    }
+}
+
+// Ranges become arrays of their numbers.
+fn to_json(interpreter: &mut Interpreter, obj: &Rc<Object>) -> Result<Json, EvalError> {
+    match &**obj {
+        Object::Nil => Ok(Json::Null),
+        Object::Bool(b) => Ok(Json::Bool(*b)),
+        Object::Num(n) if n.is_finite() => Ok(Json::Num(*n)),
+        Object::Str(s) => Ok(Json::str(s)),
+        Object::Range { end, .. } if end.is_finite() => {
+            let mut items = Vec::new();
+            for item in interpreter.collect_items(obj.clone())? {
+                items.push(to_json(interpreter, &item)?);
+            }
+            Ok(Json::Array(items))
+        }
+        obj => Err(EvalError::new(&format!("Can't convert {} to JSON.", obj))),
+    }
+}
+
+#[derive(Debug)]
+pub struct TypeFn {}
+
+impl Callable for TypeFn {
+    fn arity(&self) -> usize {
+        1
+    }
+
+    fn name(&self) -> Option<&str> {
+        Some("type")
+    }
+
+    fn call(
+        &self,
+        _interpreter: &mut Interpreter,
+        args: &[Rc<Object>],
+    ) -> Result<Rc<Object>, EvalError> {
+        let name = match &*args[0] {
+            Object::Nil => "nil",
+            Object::Num(_) => "number",
+            Object::Str(_) => "string",
+            Object::Bool(_) => "bool",
+            Object::Fun(_) => "function",
+            Object::Module(_) => "module",
+            Object::Range { .. } => "range",
+        };
+        Ok(Rc::new(Object::Str(name.to_string())))
+    }
+}
+
+#[derive(Debug)]
+pub struct ArityFn {}
+
+impl Callable for ArityFn {
+    fn arity(&self) -> usize {
+        1
+    }
+
+    fn name(&self) -> Option<&str> {
+        Some("arity")
+    }
+
+    fn call(
+        &self,
+        _interpreter: &mut Interpreter,
+        args: &[Rc<Object>],
+    ) -> Result<Rc<Object>, EvalError> {
+        let Object::Fun(f) = &*args[0] else {
+            return Err(EvalError::new("Expect function."));
+        };
+        Ok(Rc::new(Object::Num(f.arity() as f64)))
+    }
+}
+
+#[derive(Debug)]
+pub struct NameFn {}
+
+impl Callable for NameFn {
+    fn arity(&self) -> usize {
+        1
+    }
+
+    fn name(&self) -> Option<&str> {
+        Some("name")
+    }
+
+    // Nil for functions without a name, like the iterators `split` returns.
+    fn call(
+        &self,
+        _interpreter: &mut Interpreter,
+        args: &[Rc<Object>],
+    ) -> Result<Rc<Object>, EvalError> {
+        let Object::Fun(f) = &*args[0] else {
+            return Err(EvalError::new("Expect function."));
+        };
+        match f.name() {
+            Some(name) => Ok(Rc::new(Object::Str(name.to_string()))),
+            None => Ok(Rc::new(Object::Nil)),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct IsCallableFn {}
+
+impl Callable for IsCallableFn {
+    fn arity(&self) -> usize {
+        1
+    }
+
+    fn name(&self) -> Option<&str> {
+        Some("is_callable")
+    }
+
+    fn call(
+        &self,
+        _interpreter: &mut Interpreter,
+        args: &[Rc<Object>],
+    ) -> Result<Rc<Object>, EvalError> {
+        Ok(Rc::new(Object::Bool(matches!(&*args[0], Object::Fun(_)))))
     }
 }
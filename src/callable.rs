@@ -8,6 +8,15 @@ use std::rc::Rc;
 
 pub trait Callable {
     fn arity(&self) -> usize;
+    // Natives with optional trailing parameters accept up to this many
+    // arguments, or any number for `usize::MAX`.
+    fn max_arity(&self) -> usize {
+        self.arity()
+    }
+    // The name the function was declared or registered under.
+    fn name(&self) -> Option<&str> {
+        None
+    }
     fn call(
         &self,
         interpreter: &mut Interpreter,
@@ -23,3 +32,14 @@ impl Debug for dyn Callable {
         self.debug(f)
     }
 }
+
+// The accepted argument counts, as worded in arity errors.
+pub fn describe_arity(min: usize, max: usize) -> String {
+    if min == max {
+        min.to_string()
+    } else if max == usize::MAX {
+        format!("at least {}", min)
+    } else {
+        format!("{} to {}", min, max)
+    }
+}
@@ -45,6 +45,11 @@ impl Environment {
         self.env.insert(name.to_string(), value);
     }
 
+    // The value bound to `name` in this scope, ignoring outer scopes.
+    pub fn get(&self, name: &str) -> Option<Rc<Object>> {
+        self.env.get(name).cloned()
+    }
+
     pub fn lookup(&self, id: Token, depth: u32) -> Result<Rc<Object>, EvalError> {
         let name = match id.ttype {
             TokenType::Identifier(ref name) => name.clone(),
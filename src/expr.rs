@@ -1,4 +1,5 @@
 use crate::object::Object;
+use crate::printer::print_expr;
 use crate::token::Token;
 use std::rc::Rc;
 
@@ -20,17 +21,60 @@ pub enum Expr {
         paren: Token,
         args: Rc<Vec<Expr>>,
     },
+    // `x += 1` and the like, which evaluate the target only once.
+    CompoundAssign {
+        variable: Box<Expr>,
+        operator: Token,
+        value: Box<Expr>,
+    },
+    // `condition ? then_branch : else_branch`
+    Conditional {
+        condition: Box<Expr>,
+        then_branch: Box<Expr>,
+        else_branch: Box<Expr>,
+    },
+    // `object.name`, for names in an imported module.
+    Get {
+        object: Box<Expr>,
+        name: Token,
+    },
     Grouping {
         expr: Box<Expr>,
     },
+    // `++x`, `x++`, `--x` and `x--`.
+    Increment {
+        variable: Box<Expr>,
+        operator: Token,
+        postfix: bool,
+    },
+    // `object[index]`, where the index may be a range.
+    Index {
+        object: Box<Expr>,
+        bracket: Token,
+        index: Box<Expr>,
+    },
+    // Alternates string segments and interpolated expressions, starting
+    // and ending with a segment.
+    Interpolation {
+        parts: Vec<Expr>,
+    },
     Literal {
         value: Object,
+        // How a number literal was written, e.g. `0xFF`, for the printer.
+        lexeme: Option<String>,
     },
     Logic {
         left: Box<Expr>,
         operator: Token,
         right: Box<Expr>,
     },
+    // `start..end` or `start..=end`, with an optional `step`.
+    Range {
+        start: Box<Expr>,
+        operator: Token,
+        end: Box<Expr>,
+        step: Option<Box<Expr>>,
+    },
     Unary {
         operator: Token,
         right: Box<Expr>,
@@ -43,7 +87,6 @@ pub enum Expr {
 
 impl fmt::Display for Expr {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let s = format!("{:?}", self);
-        write!(f, "{}", s)
+        write!(f, "{}", print_expr(self))
     }
 }
@@ -0,0 +1,211 @@
+use crate::callable::Callable;
+use crate::interpreter::Interpreter;
+use crate::lox_error::EvalError;
+use crate::object::Object;
+use crate::strings::ItemsFn;
+
+use std::fs;
+use std::io;
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+use std::rc::Rc;
+
+// What the file natives may touch. Scripts get no file access unless the
+// embedder (or the `--allow-fs` / `--fs-root` flags) asks for it.
+#[derive(Debug)]
+pub enum FsPolicy {
+    Deny,
+    Allow,
+    // Only paths inside this canonical directory, which relative paths are
+    // resolved against.
+    Root(PathBuf),
+}
+
+impl FsPolicy {
+    pub fn root(dir: &str) -> io::Result<Self> {
+        Ok(FsPolicy::Root(fs::canonicalize(dir)?))
+    }
+
+    fn resolve(&self, path: &str) -> Result<PathBuf, EvalError> {
+        match self {
+            FsPolicy::Deny => Err(EvalError::new(
+                "File access is disabled. Run with --allow-fs or --fs-root <dir>.",
+            )),
+            FsPolicy::Allow => Ok(PathBuf::from(path)),
+            FsPolicy::Root(root) => {
+                let full = root.join(path);
+                // Writing through a dangling symlink would create its
+                // target, wherever that is.
+                if fs::canonicalize(&full).is_err()
+                    && fs::symlink_metadata(&full).is_ok_and(|m| m.file_type().is_symlink())
+                {
+                    return Err(EvalError::new(&format!(
+                        "Path '{}' is a dangling symlink.",
+                        path
+                    )));
+                }
+                match canonicalize_lenient(&full) {
+                    Some(full) if full.starts_with(root) => Ok(full),
+                    _ => Err(EvalError::new(&format!(
+                        "Path '{}' is outside the allowed directory.",
+                        path
+                    ))),
+                }
+            }
+        }
+    }
+}
+
+// Paths that don't exist yet, like the target of `write_file`, are checked
+// through their deepest existing ancestor, with the missing components
+// appended. A dangling symlink among those components is refused.
+fn canonicalize_lenient(path: &Path) -> Option<PathBuf> {
+    let components: Vec<_> = path.components().collect();
+    for i in (1..=components.len()).rev() {
+        let prefix: PathBuf = components[..i].iter().collect();
+        let Ok(mut full) = fs::canonicalize(&prefix) else {
+            continue;
+        };
+        if let Some(missing) = components.get(i) {
+            let first_missing = prefix.join(missing);
+            if fs::symlink_metadata(first_missing).is_ok_and(|m| m.file_type().is_symlink()) {
+                return None;
+            }
+        }
+        for component in &components[i..] {
+            match component {
+                Component::CurDir => {}
+                Component::ParentDir => {
+                    full.pop();
+                }
+                component => full.push(component),
+            }
+        }
+        return Some(full);
+    }
+    None
+}
+
+type FileOp = fn(&Path, &[Rc<Object>]) -> io::Result<Object>;
+
+// The file natives declared in every global environment.
+pub fn files() -> Vec<(&'static str, Rc<dyn Callable>)> {
+    let functions: [(&'static str, usize, &'static str, FileOp); 7] = [
+        ("read_file", 1, "read", read_file),
+        ("read_lines", 1, "read", read_lines),
+        ("write_file", 2, "write", write_file),
+        ("append_file", 2, "append to", append_file),
+        ("exists", 1, "check", exists),
+        ("list_dir", 1, "list", list_dir),
+        ("remove_file", 1, "remove", remove_file),
+    ];
+    functions
+        .into_iter()
+        .map(|(name, arity, verb, op)| {
+            let f: Rc<dyn Callable> = Rc::new(FileFn {
+                name,
+                arity,
+                verb,
+                op,
+            });
+            (name, f)
+        })
+        .collect()
+}
+
+pub struct FileFn {
+    name: &'static str,
+    arity: usize,
+    // Describes the operation in error messages.
+    verb: &'static str,
+    op: FileOp,
+}
+
+impl Callable for FileFn {
+    fn arity(&self) -> usize {
+        self.arity
+    }
+
+    fn name(&self) -> Option<&str> {
+        Some(self.name)
+    }
+
+    fn call(
+        &self,
+        interpreter: &mut Interpreter,
+        args: &[Rc<Object>],
+    ) -> Result<Rc<Object>, EvalError> {
+        let Object::Str(path) = &*args[0] else {
+            return Err(EvalError::new("Expect path string."));
+        };
+        let full = interpreter.fs_policy().resolve(path)?;
+        match (self.op)(&full, args) {
+            Ok(res) => Ok(Rc::new(res)),
+            Err(err) => Err(EvalError::new(&format!(
+                "Can't {} '{}': {}.",
+                self.verb, path, err
+            ))),
+        }
+    }
+}
+
+fn contents_arg(args: &[Rc<Object>]) -> io::Result<&str> {
+    match &*args[1] {
+        Object::Str(s) => Ok(s),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "contents must be a string",
+        )),
+    }
+}
+
+fn read_file(path: &Path, _args: &[Rc<Object>]) -> io::Result<Object> {
+    Ok(Object::Str(fs::read_to_string(path)?))
+}
+
+// An iterator function over the lines, without their line endings.
+fn read_lines(path: &Path, _args: &[Rc<Object>]) -> io::Result<Object> {
+    let lines = fs::read_to_string(path)?
+        .lines()
+        .map(|line| Rc::new(Object::Str(line.to_string())))
+        .collect();
+    Ok(Object::Fun(Rc::new(ItemsFn::new(lines))))
+}
+
+fn write_file(path: &Path, args: &[Rc<Object>]) -> io::Result<Object> {
+    fs::write(path, contents_arg(args)?)?;
+    Ok(Object::Nil)
+}
+
+fn append_file(path: &Path, args: &[Rc<Object>]) -> io::Result<Object> {
+    let contents = contents_arg(args)?;
+    let mut file = fs::OpenOptions::new()
+        .append(true)
+        .create(true)
+        .open(path)?;
+    file.write_all(contents.as_bytes())?;
+    Ok(Object::Nil)
+}
+
+fn exists(path: &Path, _args: &[Rc<Object>]) -> io::Result<Object> {
+    Ok(Object::Bool(path.exists()))
+}
+
+// An iterator function over the entry names, in sorted order.
+fn list_dir(path: &Path, _args: &[Rc<Object>]) -> io::Result<Object> {
+    let mut names = Vec::new();
+    for entry in fs::read_dir(path)? {
+        names.push(entry?.file_name().to_string_lossy().into_owned());
+    }
+    names.sort();
+    let names = names
+        .into_iter()
+        .map(|name| Rc::new(Object::Str(name)))
+        .collect();
+    Ok(Object::Fun(Rc::new(ItemsFn::new(names))))
+}
+
+fn remove_file(path: &Path, _args: &[Rc<Object>]) -> io::Result<Object> {
+    fs::remove_file(path)?;
+    Ok(Object::Nil)
+}
@@ -0,0 +1,197 @@
+use crate::lox_error::EvalError;
+use crate::object::Object;
+
+use std::rc::Rc;
+
+// Fills each `{}` or `{:spec}` in `template` with the next argument, where a
+// spec is `[[fill]align][0][width][.precision][type]`: align is `<`, `>` or
+// `^`, and type is `x`, `X`, `b` or `o` for integers in another base, or `e`
+// or `E` for scientific notation. `{{` and `}}` stand for literal braces.
+pub fn format(template: &str, args: &[Rc<Object>]) -> Result<String, EvalError> {
+    let mut out = String::new();
+    let mut args = args.iter();
+    let mut chars = template.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                out.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                out.push('}');
+            }
+            '{' => {
+                let mut spec = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(ch) => spec.push(ch),
+                        None => return Err(EvalError::new("Unmatched '{' in format string.")),
+                    }
+                }
+                let Some(arg) = args.next() else {
+                    return Err(EvalError::new("Too few arguments for format string."));
+                };
+                let spec = match spec.strip_prefix(':') {
+                    Some(spec) => Spec::parse(spec)?,
+                    None if spec.is_empty() => Spec::default(),
+                    None => return Err(invalid_spec(&spec)),
+                };
+                out.push_str(&spec.apply(arg)?);
+            }
+            '}' => return Err(EvalError::new("Unmatched '}' in format string.")),
+            ch => out.push(ch),
+        }
+    }
+    if args.next().is_some() {
+        return Err(EvalError::new("Too many arguments for format string."));
+    }
+    Ok(out)
+}
+
+// Wider fields and longer precisions are refused, so that a spec can't ask
+// for an enormous string.
+pub const MAX_WIDTH: usize = 1024;
+
+#[derive(Default)]
+struct Spec {
+    fill: Option<char>,
+    align: Option<char>,
+    zero: bool,
+    width: usize,
+    precision: Option<usize>,
+    kind: Option<char>,
+}
+
+impl Spec {
+    fn parse(spec: &str) -> Result<Self, EvalError> {
+        let chars: Vec<char> = spec.chars().collect();
+        let mut res = Spec::default();
+        let mut i = 0;
+        let is_align = |ch: Option<&char>| matches!(ch, Some('<' | '>' | '^'));
+        if is_align(chars.get(1)) {
+            res.fill = Some(chars[0]);
+            res.align = Some(chars[1]);
+            i = 2;
+        } else if is_align(chars.first()) {
+            res.align = Some(chars[0]);
+            i = 1;
+        }
+        if chars.get(i) == Some(&'0') {
+            res.zero = true;
+            i += 1;
+        }
+        let (width, next) = number(&chars, i);
+        if next > i {
+            res.width = width.ok_or_else(|| invalid_spec(spec))?;
+        }
+        i = next;
+        if chars.get(i) == Some(&'.') {
+            let (precision, next) = number(&chars, i + 1);
+            if precision.is_none() {
+                return Err(invalid_spec(spec));
+            }
+            res.precision = precision;
+            i = next;
+        }
+        if let Some(&kind @ ('x' | 'X' | 'b' | 'o' | 'e' | 'E')) = chars.get(i) {
+            res.kind = Some(kind);
+            i += 1;
+        }
+        if i != chars.len() {
+            return Err(invalid_spec(spec));
+        }
+        Ok(res)
+    }
+
+    fn apply(&self, arg: &Object) -> Result<String, EvalError> {
+        let body = match (self.kind, arg) {
+            (None, Object::Num(n)) => match self.precision {
+                Some(precision) => format!("{:.*}", precision, n),
+                None => n.to_string(),
+            },
+            (None, Object::Str(s)) => match self.precision {
+                Some(precision) => s.chars().take(precision).collect(),
+                None => s.clone(),
+            },
+            (None, obj) => obj.to_string(),
+            (Some(kind @ ('e' | 'E')), Object::Num(n)) => {
+                let s = match self.precision {
+                    Some(precision) => format!("{:.*e}", precision, n),
+                    None => format!("{:e}", n),
+                };
+                if kind == 'E' {
+                    s.to_uppercase()
+                } else {
+                    s
+                }
+            }
+            (Some(kind), Object::Num(n)) if n.fract() == 0.0 && n.abs() < 2f64.powi(63) => {
+                let sign = if *n < 0.0 { "-" } else { "" };
+                let n = n.abs() as u64;
+                let digits = match kind {
+                    'x' => format!("{:x}", n),
+                    'X' => format!("{:X}", n),
+                    'b' => format!("{:b}", n),
+                    _ => format!("{:o}", n),
+                };
+                format!("{}{}", sign, digits)
+            }
+            (Some(kind), _) => {
+                let expected = if matches!(kind, 'e' | 'E') {
+                    "a number"
+                } else {
+                    "an integer"
+                };
+                return Err(EvalError::new(&format!(
+                    "Format '{{:{}}}' expects {}.",
+                    kind, expected
+                )));
+            }
+        };
+        Ok(self.pad(body, matches!(arg, Object::Num(_))))
+    }
+
+    // Numbers are right-aligned by default and everything else left-aligned.
+    // Zero padding goes after a number's sign.
+    fn pad(&self, body: String, is_num: bool) -> String {
+        let len = body.chars().count();
+        if len >= self.width {
+            return body;
+        }
+        let padding = self.width - len;
+        if self.zero && is_num && self.align.is_none() {
+            let (sign, digits) = match body.strip_prefix('-') {
+                Some(digits) => ("-", digits),
+                None => ("", body.as_str()),
+            };
+            return format!("{}{}{}", sign, "0".repeat(padding), digits);
+        }
+        let fill = self.fill.unwrap_or(' ');
+        let (before, after) = match self.align {
+            Some('<') => (0, padding),
+            Some('^') => (padding / 2, padding - padding / 2),
+            Some(_) => (padding, 0),
+            None if is_num => (padding, 0),
+            None => (0, padding),
+        };
+        let repeat = |n: usize| fill.to_string().repeat(n);
+        format!("{}{}{}", repeat(before), body, repeat(after))
+    }
+}
+
+// Reads the decimal number starting at `i`, if any and at most MAX_WIDTH,
+// and the index after its digits.
+fn number(chars: &[char], i: usize) -> (Option<usize>, usize) {
+    let digits: String = chars[i.min(chars.len())..]
+        .iter()
+        .take_while(|ch| ch.is_ascii_digit())
+        .collect();
+    let n = digits.parse().ok().filter(|n| *n <= MAX_WIDTH);
+    (n, i + digits.len())
+}
+
+fn invalid_spec(spec: &str) -> EvalError {
+    EvalError::new(&format!("Invalid format spec '{}'.", spec))
+}
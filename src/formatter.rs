@@ -0,0 +1,19 @@
+use crate::lox_error::LoxError;
+use crate::parser::Parser;
+use crate::printer::print_program;
+use crate::scanner::Scanner;
+
+// Reformats Lox source in canonical style, keeping comments and
+// collapsing runs of blank lines into one.
+pub fn format_source(source: String) -> Result<String, LoxError> {
+    let mut scanner = Scanner::with_comments(source.clone());
+    let tokens = scanner.scan_tokens()?;
+    let mut parser = Parser::with_trivia(tokens, &source);
+    let stmts = parser.parse()?;
+    // Rather than move a comment away from the code it describes, leave
+    // the file alone.
+    if parser.stranded_comment() {
+        return Ok(source);
+    }
+    Ok(print_program(&stmts))
+}
@@ -2,6 +2,7 @@ use crate::callable::Callable;
 use crate::environment::Environment;
 use crate::interpreter::Interpreter;
 use crate::lox_error::EvalError;
+use crate::module::Module;
 use crate::object::Object;
 use crate::stmt::Stmt;
 use crate::token::{Token, TokenType};
@@ -15,6 +16,8 @@ pub struct Function {
     params: Vec<Token>,
     body: Rc<Vec<Stmt>>,
     closure: Rc<RefCell<Environment>>,
+    // The module the function was declared in, whose globals it sees.
+    module: Rc<Module>,
 }
 
 impl Function {
@@ -23,6 +26,7 @@ impl Function {
         params: Vec<Token>,
         body: Rc<Vec<Stmt>>,
         env: Rc<RefCell<Environment>>,
+        module: Rc<Module>,
     ) -> Self {
         let closure = env;
         Function {
@@ -30,6 +34,7 @@ impl Function {
             params,
             body,
             closure,
+            module,
         }
     }
 }
@@ -39,6 +44,10 @@ impl Callable for Function {
         self.params.len()
     }
 
+    fn name(&self) -> Option<&str> {
+        Some(&self.name.lexeme)
+    }
+
     fn call(
         &self,
         interpreter: &mut Interpreter,
@@ -53,8 +62,9 @@ impl Callable for Function {
                 TokenType::Identifier(name) => scope.borrow_mut().declare(&name, a.clone()),
                 _ => unreachable!(),
             });
-        match interpreter.execute_with_env(&self.body, scope) {
+        match interpreter.execute_with_env(&self.body, scope, self.module.clone()) {
             Ok(obj) => Ok(obj),
+            Err(EvalError::Exit(code)) => Err(EvalError::Exit(code)),
             Err(EvalError::Runtime(msg)) => Err(EvalError::new(&msg)),
             Err(EvalError::Return(obj)) => Ok(obj),
         }
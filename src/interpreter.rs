@@ -1,44 +1,95 @@
-use crate::builtins::*;
+use crate::callable::{describe_arity, Callable};
 use crate::environment::Environment;
 use crate::expr;
 use crate::expr::Expr;
+use crate::files::FsPolicy;
 use crate::function::Function;
 use crate::lox_error::{EvalError, LoxError};
-use crate::object::{stringify_cli_result, Object};
-use crate::stmt::Stmt;
+use crate::module::Module;
+use crate::object::Object;
+use crate::parser::Parser;
+use crate::scanner::Scanner;
+use crate::stmt::{MatchArm, Pattern, Stmt};
 use crate::token::{Token, TokenType};
 
+use rand::rngs::StdRng;
+use rand::SeedableRng;
 use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 
+// The most items a native will gather from one iterable.
+const MAX_COLLECTED_ITEMS: usize = 1 << 20;
+
 pub struct Interpreter {
     is_repl: bool,
-    global_env: Rc<RefCell<Environment>>,
+    // The module whose code is running, which holds the globals.
+    module: Rc<Module>,
     local_env: Rc<RefCell<Environment>>,
+    // Imported modules by canonical path, so each runs only once.
+    modules: HashMap<PathBuf, Rc<Module>>,
+    // The chain of files being imported, for detecting cycles.
+    importing: Vec<PathBuf>,
+    fs_policy: FsPolicy,
+    // Shared by the random natives, so that a seed makes a run repeatable.
+    rng: StdRng,
+    // The command-line arguments after the script path.
+    script_args: Vec<String>,
 }
 
 impl Interpreter {
     pub fn new() -> Self {
-        let mut global_env = Environment::new();
-        global_env.declare("clock", Rc::new(Object::Fun(Rc::new(ClockFn {}))));
-        global_env.declare("input", Rc::new(Object::Fun(Rc::new(InputFn {}))));
-        global_env.declare("num", Rc::new(Object::Fun(Rc::new(NumFn {}))));
-        global_env.declare("rand_int", Rc::new(Object::Fun(Rc::new(RandIntFn {}))));
-        global_env.declare("str", Rc::new(Object::Fun(Rc::new(StrFn {}))));
-
         Interpreter {
             is_repl: false,
-            global_env: Rc::new(RefCell::new(global_env)),
+            module: Rc::new(Module::new(PathBuf::new())),
             local_env: Rc::new(RefCell::new(Environment::new())),
+            modules: HashMap::new(),
+            importing: Vec::new(),
+            fs_policy: FsPolicy::Deny,
+            rng: StdRng::from_entropy(),
+            script_args: Vec::new(),
         }
     }
 
+    // Imports in the script are resolved relative to its path.
+    pub fn set_script_path(&mut self, path: &Path) {
+        self.module = Rc::new(Module::new(path.to_path_buf()));
+        self.importing = fs::canonicalize(path).into_iter().collect();
+    }
+
+    pub fn set_fs_policy(&mut self, policy: FsPolicy) {
+        self.fs_policy = policy;
+    }
+
+    pub fn fs_policy(&self) -> &FsPolicy {
+        &self.fs_policy
+    }
+
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
+
+    pub fn rng(&mut self) -> &mut StdRng {
+        &mut self.rng
+    }
+
+    pub fn set_script_args(&mut self, args: Vec<String>) {
+        self.script_args = args;
+    }
+
+    pub fn script_args(&self) -> &[String] {
+        &self.script_args
+    }
+
     pub fn interpret(&mut self, stmts: Vec<Stmt>) -> Result<Rc<Object>, LoxError> {
         let mut hit_error = false;
         let mut last_result = Rc::new(Object::Nil);
         for stmt in stmts {
             match self.execute(&stmt) {
                 Ok(obj) => last_result = obj,
+                Err(EvalError::Exit(code)) => return Err(LoxError::Exit(code)),
                 Err(err) => {
                     err.report();
                     hit_error = true;
@@ -56,17 +107,21 @@ impl Interpreter {
         &mut self,
         stmts: &[Stmt],
         env: Rc<RefCell<Environment>>,
+        module: Rc<Module>,
     ) -> Result<Rc<Object>, EvalError> {
         let prev_env = self.local_env.clone();
+        let prev_module = std::mem::replace(&mut self.module, module);
         self.local_env = env;
         let res = self.execute_block_with_current_scope(stmts);
         self.local_env = prev_env;
+        self.module = prev_module;
         res
     }
 
     fn execute(&mut self, stmt: &Stmt) -> Result<Rc<Object>, EvalError> {
         use Stmt::*;
         match stmt {
+            BlankLine | Comment { .. } | Verbatim { .. } => Ok(Rc::new(Object::Nil)),
             Block { stmts } => self.execute_block(stmts),
             Expr { expr } => self.evaluate(expr),
             For {
@@ -81,6 +136,7 @@ impl Interpreter {
                 }
                 let tr = expr::Expr::Literal {
                     value: Object::Bool(true),
+                    lexeme: None,
                 };
                 while is_truthy(&self.evaluate(condition.as_ref().unwrap_or(&tr))?) {
                     self.execute(block)?;
@@ -91,6 +147,16 @@ impl Interpreter {
                 self.local_env = self.local_env.clone().borrow().remove_scope()?;
                 Ok(Rc::new(Object::Nil))
             }
+            ForIn {
+                variable,
+                iterable,
+                block,
+            } => {
+                let prev_env = self.local_env.clone();
+                let res = self.execute_for_in(variable, iterable, block);
+                self.local_env = prev_env;
+                res
+            }
             Fun {
                 name,
                 params,
@@ -102,6 +168,7 @@ impl Interpreter {
                     params.clone(),
                     body.clone(),
                     self.local_env.clone(),
+                    self.module.clone(),
                 ));
                 let fobj = Rc::new(Object::Fun(f));
                 if depth.is_some() {
@@ -109,7 +176,8 @@ impl Interpreter {
                         .borrow_mut()
                         .declare(&name.lexeme, fobj.clone());
                 } else {
-                    self.global_env
+                    self.module
+                        .globals
                         .borrow_mut()
                         .declare(&name.lexeme, fobj.clone());
                 }
@@ -131,19 +199,73 @@ impl Interpreter {
                     Ok(Rc::new(Object::Nil))
                 }
             }
+            Import {
+                keyword,
+                path,
+                alias,
+                names,
+                depth,
+            } => {
+                let module = self
+                    .import(path)
+                    .map_err(|err| err.with_context(keyword.clone(), &format!("{:?}", path)))?;
+                let mut bindings = Vec::new();
+                match alias {
+                    Some(alias) => bindings.push((alias, Rc::new(Object::Module(module)))),
+                    None => {
+                        for name in names {
+                            let Some(value) = module.globals.borrow().get(&name.lexeme) else {
+                                return Err(EvalError::new(&format!(
+                                    "Module '{}' has no name '{}'.",
+                                    module.name, name.lexeme
+                                ))
+                                .with_context(name.clone(), &name.lexeme));
+                            };
+                            bindings.push((name, value));
+                        }
+                    }
+                }
+                let env = if depth.is_some() {
+                    &self.local_env
+                } else {
+                    &self.module.globals
+                };
+                for (name, value) in bindings {
+                    env.borrow_mut().declare(&name.lexeme, value);
+                }
+                Ok(Rc::new(Object::Nil))
+            }
+            Match {
+                keyword,
+                value,
+                arms,
+            } => {
+                let obj = self.evaluate(value)?;
+                for arm in arms {
+                    let prev_env = self.local_env.clone();
+                    self.local_env = Environment::add_scope(prev_env.clone());
+                    let res = self.execute_arm(arm, &obj);
+                    self.local_env = prev_env;
+                    if let Some(res) = res? {
+                        return Ok(res);
+                    }
+                }
+                Err(EvalError::new(&format!("No match arm for value {}.", obj))
+                    .with_context(keyword.clone(), &value.to_string()))
+            }
             Print { expr } => {
                 let obj = self.evaluate(expr)?;
-                println!("{}", stringify_cli_result(&obj));
+                println!("{}", obj);
                 Ok(Rc::new(Object::Nil))
             }
-            Return { expr } => Err(EvalError::new_return(self.evaluate(expr)?)),
+            Return { expr, .. } => Err(EvalError::new_return(self.evaluate(expr)?)),
             VarDecl { variable, value } => {
                 let val = self.evaluate(value)?;
                 let (name, depth) = name_and_depth_for(variable)?;
                 if depth.is_some() {
                     self.local_env.borrow_mut().declare(&name.lexeme, val);
                 } else {
-                    self.global_env.borrow_mut().declare(&name.lexeme, val);
+                    self.module.globals.borrow_mut().declare(&name.lexeme, val);
                 }
                 Ok(Rc::new(Object::Nil))
             }
@@ -156,6 +278,135 @@ impl Interpreter {
         }
     }
 
+    // Runs the file at `path` the first time it is imported, in a module of
+    // its own.
+    fn import(&mut self, path: &str) -> Result<Rc<Module>, EvalError> {
+        // Native modules are cached under their bare name, which can't clash
+        // with the canonical path of a file.
+        if let Some(module) = self.modules.get(Path::new(path)) {
+            return Ok(module.clone());
+        }
+        if let Some(module) = Module::native(path) {
+            let module = Rc::new(module);
+            self.modules.insert(PathBuf::from(path), module.clone());
+            return Ok(module);
+        }
+        let file = fs::canonicalize(self.module.resolve(path))
+            .map_err(|err| EvalError::new(&format!("Can't import '{}': {}.", path, err)))?;
+        if let Some(module) = self.modules.get(&file) {
+            return Ok(module.clone());
+        }
+        if let Some(start) = self.importing.iter().position(|p| *p == file) {
+            let cycle: Vec<_> = self.importing[start..]
+                .iter()
+                .chain([&file])
+                .map(|p| p.file_name().unwrap_or_default().to_string_lossy())
+                .collect();
+            return Err(EvalError::new(&format!(
+                "Import cycle: {}.",
+                cycle.join(" -> ")
+            )));
+        }
+        let source = fs::read_to_string(&file)
+            .map_err(|err| EvalError::new(&format!("Can't import '{}': {}.", path, err)))?;
+        let stmts = Scanner::new(source)
+            .scan_tokens()
+            .and_then(|tokens| Parser::new(tokens).parse())
+            .map_err(|_| EvalError::new(&format!("Failed to compile module '{}'.", path)))?;
+
+        let module = Rc::new(Module::new(file.clone()));
+        self.importing.push(file.clone());
+        let prev_module = std::mem::replace(&mut self.module, module.clone());
+        let prev_env = std::mem::replace(
+            &mut self.local_env,
+            Rc::new(RefCell::new(Environment::new())),
+        );
+        let res = stmts
+            .iter()
+            .try_for_each(|stmt| self.execute(stmt).map(|_| ()));
+        self.module = prev_module;
+        self.local_env = prev_env;
+        self.importing.pop();
+        res?;
+        self.modules.insert(file, module.clone());
+        Ok(module)
+    }
+
+    // Iterates over the characters of a string, or calls an iterator function
+    // until it returns nil.
+    fn execute_for_in(
+        &mut self,
+        variable: &Token,
+        iterable: &Expr,
+        block: &Stmt,
+    ) -> Result<Rc<Object>, EvalError> {
+        let outer_env = self.local_env.clone();
+        // The iterable is resolved inside the loop's scope.
+        self.local_env = Environment::add_scope(outer_env.clone());
+        let obj = self.evaluate(iterable)?;
+        let mut iter = Iteration::new(obj)
+            .map_err(|err| err.with_context(variable.clone(), &iterable.to_string()))?;
+        while let Some(item) = iter.next(self)? {
+            self.local_env = Environment::add_scope(outer_env.clone());
+            self.local_env.borrow_mut().declare(&variable.lexeme, item);
+            self.execute(block)?;
+        }
+        Ok(Rc::new(Object::Nil))
+    }
+
+    // Everything a `for-in` loop over `obj` would go through, for natives
+    // that take an iterable. Huge or endless iterables are refused.
+    pub fn collect_items(&mut self, obj: Rc<Object>) -> Result<Vec<Rc<Object>>, EvalError> {
+        let mut iter = Iteration::new(obj)?;
+        let mut items = Vec::new();
+        while let Some(item) = iter.next(self)? {
+            if items.len() == MAX_COLLECTED_ITEMS {
+                return Err(EvalError::new(&format!(
+                    "Can't take more than {} items from an iterable.",
+                    MAX_COLLECTED_ITEMS
+                )));
+            }
+            items.push(item);
+        }
+        Ok(items)
+    }
+
+    // Runs the arm's body if its pattern and guard match, in the arm's scope.
+    fn execute_arm(
+        &mut self,
+        arm: &MatchArm,
+        obj: &Rc<Object>,
+    ) -> Result<Option<Rc<Object>>, EvalError> {
+        let matched = match &arm.pattern {
+            Pattern::Binding(name) => {
+                self.local_env
+                    .borrow_mut()
+                    .declare(&name.lexeme, obj.clone());
+                true
+            }
+            Pattern::Literals(literals) => {
+                let mut matched = false;
+                for literal in literals {
+                    if is_equal(self.evaluate(literal)?, obj.clone()) {
+                        matched = true;
+                        break;
+                    }
+                }
+                matched
+            }
+            Pattern::Wildcard => true,
+        };
+        if !matched {
+            return Ok(None);
+        }
+        if let Some(guard) = &arm.guard {
+            if !is_truthy(&self.evaluate(guard)?) {
+                return Ok(None);
+            }
+        }
+        self.execute(&arm.body).map(Some)
+    }
+
     fn execute_block(&mut self, stmts: &[Stmt]) -> Result<Rc<Object>, EvalError> {
         self._execute_block(stmts, false)
     }
@@ -182,10 +433,11 @@ impl Interpreter {
                 Ok(obj) => {
                     last_res = obj.clone();
                     if self.is_repl {
-                        println!("val: {}", stringify_cli_result(&obj));
+                        println!("val: {}", obj);
                     }
                 }
                 Err(EvalError::Return(obj)) => return Err(EvalError::Return(obj)),
+                Err(EvalError::Exit(code)) => return Err(EvalError::Exit(code)),
                 Err(err) => {
                     err.report();
                     last_error = Some(err);
@@ -207,12 +459,7 @@ impl Interpreter {
         match expr {
             Assign { variable, value } => {
                 let val = self.evaluate(value)?;
-                let (name, depth) = name_and_depth_for(variable)?;
-                if depth.is_some() {
-                    self.local_env.borrow_mut().assign(name, val.clone())?;
-                } else {
-                    self.global_env.borrow_mut().assign(name, val.clone())?;
-                }
+                self.assign(variable, val.clone())?;
                 Ok(val)
             }
             Binary {
@@ -228,8 +475,55 @@ impl Interpreter {
                 ref paren,
                 ref args,
             } => self.eval_call(callee, paren, args),
+            CompoundAssign {
+                variable,
+                operator,
+                value,
+            } => match self.eval_compound_assign(variable, operator, value) {
+                Ok(exp) => Ok(exp),
+                Err(everr) => Err(everr.with_context(operator.clone(), &expr.to_string())),
+            },
+            Conditional {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                if is_truthy(&self.evaluate(condition)?) {
+                    self.evaluate(then_branch)
+                } else {
+                    self.evaluate(else_branch)
+                }
+            }
+            Get { object, name } => match self.eval_get(object, name) {
+                Ok(exp) => Ok(exp),
+                Err(everr) => Err(everr.with_context(name.clone(), &expr.to_string())),
+            },
             Grouping { ref expr } => self.eval_grouping(expr),
-            Literal { ref value } => {
+            Index {
+                object,
+                bracket,
+                index,
+            } => match self.eval_index(object, index) {
+                Ok(exp) => Ok(exp),
+                Err(everr) => Err(everr.with_context(bracket.clone(), &expr.to_string())),
+            },
+            Increment {
+                variable,
+                operator,
+                postfix,
+            } => match self.eval_increment(variable, operator, *postfix) {
+                Ok(exp) => Ok(exp),
+                Err(everr) => Err(everr.with_context(operator.clone(), &expr.to_string())),
+            },
+            Interpolation { ref parts } => {
+                let mut s = String::new();
+                for part in parts {
+                    let obj = self.evaluate(part)?;
+                    s.push_str(&obj.to_string());
+                }
+                Ok(Rc::new(Object::Str(s)))
+            }
+            Literal { ref value, .. } => {
                 use self::Object::*;
                 Ok(Rc::new(match value {
                     Nil => Nil,
@@ -237,6 +531,18 @@ impl Interpreter {
                     Num(n) => Num(*n),
                     Str(s) => Str(s.clone()),
                     Fun(f) => Fun(f.clone()),
+                    Module(m) => Module(m.clone()),
+                    Range {
+                        start,
+                        end,
+                        inclusive,
+                        step,
+                    } => Range {
+                        start: *start,
+                        end: *end,
+                        inclusive: *inclusive,
+                        step: *step,
+                    },
                 }))
             }
             Logic {
@@ -247,6 +553,15 @@ impl Interpreter {
                 Ok(exp) => Ok(exp),
                 Err(everr) => Err(everr.with_context(operator.clone(), &expr.to_string())),
             },
+            Range {
+                start,
+                operator,
+                end,
+                step,
+            } => match self.eval_range(start, operator, end, step) {
+                Ok(exp) => Ok(exp),
+                Err(everr) => Err(everr.with_context(operator.clone(), &expr.to_string())),
+            },
             Unary {
                 ref operator,
                 ref right,
@@ -260,11 +575,147 @@ impl Interpreter {
             } => Ok(if let Some(d) = depth {
                 self.local_env.borrow_mut().lookup(name.clone(), *d)?
             } else {
-                self.global_env.borrow_mut().lookup(name.clone(), 0)?
+                self.module.globals.borrow_mut().lookup(name.clone(), 0)?
+            }),
+        }
+    }
+
+    fn assign(&mut self, variable: &Expr, val: Rc<Object>) -> Result<(), EvalError> {
+        let (name, depth) = name_and_depth_for(variable)?;
+        if depth.is_some() {
+            self.local_env.borrow_mut().assign(name, val)
+        } else {
+            self.module.globals.borrow_mut().assign(name, val)
+        }
+    }
+
+    pub fn eval_compound_assign(
+        &mut self,
+        variable: &Expr,
+        operator: &Token,
+        value: &Expr,
+    ) -> Result<Rc<Object>, EvalError> {
+        use TokenType::*;
+        let op = match operator.ttype {
+            MinusEqual => Minus,
+            PercentEqual => Percent,
+            PlusEqual => Plus,
+            SlashEqual => Slash,
+            StarEqual => Star,
+            ref tt => {
+                return Err(EvalError::new(&format!(
+                    "eval_compound_assign: Invalid operator! {:?}",
+                    tt
+                )))
+            }
+        };
+        let current = self.evaluate(variable)?;
+        let r = self.evaluate(value)?;
+        let val = Rc::new(apply_binary(&op, current, r)?);
+        self.assign(variable, val.clone())?;
+        Ok(val)
+    }
+
+    // Postfix increments evaluate to the value before the update.
+    pub fn eval_increment(
+        &mut self,
+        variable: &Expr,
+        operator: &Token,
+        postfix: bool,
+    ) -> Result<Rc<Object>, EvalError> {
+        let current = self.evaluate(variable)?;
+        let n = match &*current {
+            Object::Num(n) => *n,
+            _ => return Err(EvalError::new("Operand must be a number.")),
+        };
+        let delta = if operator.ttype == TokenType::PlusPlus {
+            1.0
+        } else {
+            -1.0
+        };
+        let val = Rc::new(Object::Num(n + delta));
+        self.assign(variable, val.clone())?;
+        Ok(if postfix { current } else { val })
+    }
+
+    pub fn eval_get(&mut self, object: &Expr, name: &Token) -> Result<Rc<Object>, EvalError> {
+        match &*self.evaluate(object)? {
+            Object::Module(module) => module.globals.borrow().get(&name.lexeme).ok_or_else(|| {
+                EvalError::new(&format!(
+                    "Module '{}' has no name '{}'.",
+                    module.name, name.lexeme
+                ))
             }),
+            _ => Err(EvalError::new("Only modules have properties.")),
         }
     }
 
+    pub fn eval_index(&mut self, object: &Expr, index: &Expr) -> Result<Rc<Object>, EvalError> {
+        let obj = self.evaluate(object)?;
+        let index = self.evaluate(index)?;
+        let Object::Str(s) = &*obj else {
+            return Err(EvalError::new("Can only index strings."));
+        };
+        let chars: Vec<char> = s.chars().collect();
+        let char_at = |n: f64| {
+            if n.fract() != 0.0 {
+                Err(EvalError::new("Index must be an integer."))
+            } else if n < 0.0 || n >= chars.len() as f64 {
+                Err(EvalError::new("String index out of range."))
+            } else {
+                Ok(chars[n as usize])
+            }
+        };
+        Ok(Rc::new(Object::Str(match &*index {
+            Object::Num(n) => char_at(*n)?.to_string(),
+            Object::Range { .. } => {
+                let mut slice = String::new();
+                for i in 0.. {
+                    let Some(n) = range_item(&index, i) else {
+                        break;
+                    };
+                    slice.push(char_at(n)?);
+                }
+                slice
+            }
+            _ => return Err(EvalError::new("Index must be a number or a range.")),
+        })))
+    }
+
+    pub fn eval_range(
+        &mut self,
+        start: &Expr,
+        operator: &Token,
+        end: &Expr,
+        step: &Option<Box<Expr>>,
+    ) -> Result<Rc<Object>, EvalError> {
+        let bound = |obj: Rc<Object>| match &*obj {
+            Object::Num(n) => Ok(*n),
+            _ => Err(EvalError::new("Range bounds must be numbers.")),
+        };
+        let start = bound(self.evaluate(start)?)?;
+        let end = bound(self.evaluate(end)?)?;
+        let step = match step {
+            Some(step) => match &*self.evaluate(step)? {
+                Object::Num(n) if *n == 0.0 => {
+                    return Err(EvalError::new("Range step can't be 0."))
+                }
+                Object::Num(n) if !n.is_finite() => {
+                    return Err(EvalError::new("Range step must be finite."))
+                }
+                Object::Num(n) => *n,
+                _ => return Err(EvalError::new("Range step must be a number.")),
+            },
+            None => 1.0,
+        };
+        Ok(Rc::new(Object::Range {
+            start,
+            end,
+            inclusive: operator.ttype == TokenType::DotDotEqual,
+            step,
+        }))
+    }
+
     pub fn eval_grouping(&mut self, expr: &Expr) -> Result<Rc<Object>, EvalError> {
         self.evaluate(expr)
     }
@@ -277,10 +728,10 @@ impl Interpreter {
     ) -> Result<Rc<Object>, EvalError> {
         match &*self.evaluate(callee)? {
             Object::Fun(f) => {
-                if args.len() != f.arity() {
+                if args.len() < f.arity() || args.len() > f.max_arity() {
                     return Err(EvalError::new(&format!(
                         "Expected {} arguments but got {}.",
-                        f.arity(),
+                        describe_arity(f.arity(), f.max_arity()),
                         args.len()
                     ))
                     .with_context(paren.clone(), &callee.to_string()));
@@ -307,6 +758,7 @@ impl Interpreter {
                 Num(n) => Num(-n),
                 _ => return Err(EvalError::new("Operand must be a number.")),
             },
+            Tilde => Num(!as_int(r)? as f64),
             tt => {
                 return Err(EvalError::new(&format!(
                     "eval_unary: Invalid operator! {:?}",
@@ -324,27 +776,7 @@ impl Interpreter {
     ) -> Result<Rc<Object>, EvalError> {
         let l = self.evaluate(left)?;
         let r = self.evaluate(right)?;
-
-        use self::Object::*;
-        use TokenType::*;
-        Ok(Rc::new(match &operator.ttype {
-            BangEqual => Bool(!is_equal(l, r)),
-            EqualEqual => Bool(is_equal(l, r)),
-            Greater => Bool(as_num(l)? > as_num(r)?),
-            GreaterEqual => Bool(as_num(l)? >= as_num(r)?),
-            Less => Bool(as_num(l)? < as_num(r)?),
-            LessEqual => Bool(as_num(l)? <= as_num(r)?),
-            Minus => Num(as_num(l)? - as_num(r)?),
-            Plus => eval_plus(l, r)?,
-            Slash => eval_div(l, r)?,
-            Star => Num(as_num(l)? * as_num(r)?),
-            tt => {
-                return Err(EvalError::new(&format!(
-                    "eval_binary: Invalid operator! {:?}",
-                    tt
-                )))
-            }
-        }))
+        Ok(Rc::new(apply_binary(&operator.ttype, l, r)?))
     }
 
     pub fn eval_logic(
@@ -394,6 +826,142 @@ fn eval_plus(l: Rc<Object>, r: Rc<Object>) -> Result<Object, EvalError> {
     }
 }
 
+// Operators that evaluate both operands, shared with compound assignment.
+fn apply_binary(op: &TokenType, l: Rc<Object>, r: Rc<Object>) -> Result<Object, EvalError> {
+    use self::Object::*;
+    use TokenType::*;
+    Ok(match op {
+        Ampersand => Num((as_int(l)? & as_int(r)?) as f64),
+        In => eval_in(l, r)?,
+        BangEqual => Bool(!is_equal(l, r)),
+        Caret => Num((as_int(l)? ^ as_int(r)?) as f64),
+        EqualEqual => Bool(is_equal(l, r)),
+        Greater => Bool(as_num(l)? > as_num(r)?),
+        GreaterEqual => Bool(as_num(l)? >= as_num(r)?),
+        GreaterGreater => eval_shift(l, r, i64::checked_shr)?,
+        Less => Bool(as_num(l)? < as_num(r)?),
+        LessEqual => Bool(as_num(l)? <= as_num(r)?),
+        LessLess => eval_shift(l, r, i64::checked_shl)?,
+        Minus => Num(as_num(l)? - as_num(r)?),
+        Percent => eval_mod(l, r)?,
+        Pipe => Num((as_int(l)? | as_int(r)?) as f64),
+        Plus => eval_plus(l, r)?,
+        Slash => eval_div(l, r)?,
+        Star => Num(as_num(l)? * as_num(r)?),
+        StarStar => Num(as_num(l)?.powf(as_num(r)?)),
+        TildeSlash => eval_int_div(l, r)?,
+        tt => {
+            return Err(EvalError::new(&format!(
+                "apply_binary: Invalid operator! {:?}",
+                tt
+            )))
+        }
+    })
+}
+
+// The state of a `for-in` loop.
+enum Iteration {
+    Chars(std::vec::IntoIter<char>),
+    // An iterator function, called until it returns nil.
+    Calls(Rc<dyn Callable>),
+    // A range and the index of its next item.
+    Range(Rc<Object>, usize),
+}
+
+impl Iteration {
+    fn new(obj: Rc<Object>) -> Result<Self, EvalError> {
+        Ok(match &*obj {
+            Object::Str(s) => Iteration::Chars(s.chars().collect::<Vec<_>>().into_iter()),
+            Object::Fun(f) if f.arity() == 0 => Iteration::Calls(f.clone()),
+            Object::Range { .. } => Iteration::Range(obj.clone(), 0),
+            _ => {
+                return Err(EvalError::new(
+                    "Can only iterate over strings, ranges and iterator functions.",
+                ))
+            }
+        })
+    }
+
+    fn next(&mut self, interpreter: &mut Interpreter) -> Result<Option<Rc<Object>>, EvalError> {
+        Ok(match self {
+            Iteration::Chars(chars) => chars.next().map(|ch| Rc::new(Object::Str(ch.to_string()))),
+            Iteration::Calls(f) => {
+                let item = f.call(interpreter, &[])?;
+                match *item {
+                    Object::Nil => None,
+                    _ => Some(item),
+                }
+            }
+            Iteration::Range(range, i) => {
+                *i += 1;
+                range_item(range, *i - 1).map(|n| Rc::new(Object::Num(n)))
+            }
+        })
+    }
+}
+
+// The `i`th item of a range, computed from the start so that fractional
+// steps don't accumulate rounding errors.
+pub fn range_item(range: &Object, i: usize) -> Option<f64> {
+    let Object::Range {
+        start,
+        end,
+        inclusive,
+        step,
+    } = *range
+    else {
+        return None;
+    };
+    let n = start + step * i as f64;
+    let before_end = if step > 0.0 { n < end } else { n > end };
+    (before_end || (inclusive && n == end)).then_some(n)
+}
+
+// How many items a range has, or `None` for one too long to index exactly.
+pub fn range_len(range: &Object) -> Option<usize> {
+    let Object::Range {
+        start, end, step, ..
+    } = *range
+    else {
+        return None;
+    };
+    let span = ((end - start) / step).max(0.0);
+    if !span.is_finite() || span >= 2f64.powi(53) {
+        return None;
+    }
+    // The estimate can be off by one either way through rounding.
+    let mut len = span.ceil() as usize;
+    while len > 0 && range_item(range, len - 1).is_none() {
+        len -= 1;
+    }
+    while range_item(range, len).is_some() {
+        len += 1;
+    }
+    Some(len)
+}
+
+// `item in container`, for substrings of a string or numbers in a range.
+fn eval_in(item: Rc<Object>, container: Rc<Object>) -> Result<Object, EvalError> {
+    Ok(Object::Bool(match (&*item, &*container) {
+        (Object::Str(sub), Object::Str(s)) => s.contains(sub.as_str()),
+        (_, Object::Str(_)) => {
+            return Err(EvalError::new(
+                "Can only check strings for membership in a string.",
+            ))
+        }
+        (Object::Num(n), Object::Range { start, step, .. }) => {
+            let i = (n - start) / step;
+            i >= 0.0 && i.fract() == 0.0 && range_item(&container, i as usize) == Some(*n)
+        }
+        (_, Object::Range { .. }) => false,
+        _ => {
+            return Err(EvalError::new(
+                "Can only check membership in strings and ranges.",
+            ))
+        }
+    }))
+}
+
 fn eval_div(l: Rc<Object>, r: Rc<Object>) -> Result<Object, EvalError> {
     let divisor = as_num(r)?;
     if divisor == 0.0 {
@@ -403,6 +971,43 @@ fn eval_div(l: Rc<Object>, r: Rc<Object>) -> Result<Object, EvalError> {
     Ok(Object::Num(res))
 }
 
+// Lox's modulo is floored: the result takes the sign of the divisor, so
+// `-7 % 3` is 2 and `7 % -3` is -2.
+fn eval_mod(l: Rc<Object>, r: Rc<Object>) -> Result<Object, EvalError> {
+    let divisor = as_num(r)?;
+    if divisor == 0.0 {
+        return Err(EvalError::new("Tried to take modulo by 0!"));
+    }
+    let dividend = as_num(l)?;
+    Ok(Object::Num(
+        dividend - divisor * (dividend / divisor).floor(),
+    ))
+}
+
+// Integer division rounds down, matching the floored modulo.
+fn eval_int_div(l: Rc<Object>, r: Rc<Object>) -> Result<Object, EvalError> {
+    let divisor = as_num(r)?;
+    if divisor == 0.0 {
+        return Err(EvalError::new("Tried to divide by 0!"));
+    }
+    let res = (as_num(l)? / divisor).floor();
+    Ok(Object::Num(res))
+}
+
+fn eval_shift(
+    l: Rc<Object>,
+    r: Rc<Object>,
+    shift: fn(i64, u32) -> Option<i64>,
+) -> Result<Object, EvalError> {
+    let value = as_int(l)?;
+    let amount = as_int(r)?;
+    u32::try_from(amount)
+        .ok()
+        .and_then(|amount| shift(value, amount))
+        .map(|res| Object::Num(res as f64))
+        .ok_or_else(|| EvalError::new("Shift amount must be between 0 and 63."))
+}
+
 fn name_and_depth_for(variable: &Expr) -> Result<(Token, Option<u32>), EvalError> {
     match variable {
         Expr::Variable { name, depth } => Ok((name.clone(), *depth)),
@@ -414,7 +1019,7 @@ fn is_truthy(obj: &Rc<Object>) -> bool {
     use self::Object::*;
     match &*obj.clone() {
         Bool(b) => *b,
-        Num(_) | Str(_) | Fun(_) => true,
+        Num(_) | Str(_) | Fun(_) | Module(_) | Range { .. } => true,
         Nil => false,
     }
 }
@@ -428,6 +1033,21 @@ fn is_equal(l: Rc<Object>, r: Rc<Object>) -> bool {
         (Num(n1), Num(n2)) => n1 == n2,
         (Str(s1), Str(s2)) => s1 == s2,
         (Nil, Nil) => true,
+        (Module(m1), Module(m2)) => Rc::ptr_eq(m1, m2),
+        (
+            Range {
+                start: s1,
+                end: e1,
+                inclusive: i1,
+                step: st1,
+            },
+            Range {
+                start: s2,
+                end: e2,
+                inclusive: i2,
+                step: st2,
+            },
+        ) => s1 == s2 && e1 == e2 && i1 == i2 && st1 == st2,
         _ => false,
     }
 }
@@ -440,6 +1060,16 @@ fn as_num(obj: Rc<Object>) -> Result<f64, EvalError> {
     }
 }
 
+// Bitwise operators work on numbers with an exact 64-bit integer value.
+fn as_int(obj: Rc<Object>) -> Result<i64, EvalError> {
+    match &*obj {
+        Object::Num(n) if n.fract() == 0.0 && (i64::MIN as f64..i64::MAX as f64).contains(n) => {
+            Ok(*n as i64)
+        }
+        _ => Err(EvalError::new("Operands must be integers.")),
+    }
+}
+
 fn as_str(obj: Rc<Object>) -> Result<String, EvalError> {
     use self::Object::*;
     match &*obj {
@@ -0,0 +1,431 @@
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Num(f64),
+    Str(String),
+    Array(Vec<Json>),
+    // Keeps keys in insertion order.
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(fields) => fields.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    // Follows a path of object keys, e.g. ["params", "textDocument", "uri"].
+    pub fn path(&self, keys: &[&str]) -> Option<&Json> {
+        keys.iter().try_fold(self, |json, key| json.get(key))
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::Str(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_num(&self) -> Option<f64> {
+        match self {
+            Json::Num(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    pub fn object(fields: Vec<(&str, Json)>) -> Json {
+        Json::Object(
+            fields
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v))
+                .collect(),
+        )
+    }
+
+    pub fn str(s: &str) -> Json {
+        Json::Str(s.to_string())
+    }
+
+    // Puts each array item and object field on its own line, nested `indent`
+    // spaces deeper than its container. An indent of 0 gives the compact form.
+    pub fn pretty(&self, indent: usize) -> String {
+        let mut out = String::new();
+        if indent == 0 {
+            out = self.to_string();
+        } else {
+            self.write_pretty(&mut out, indent, 0);
+        }
+        out
+    }
+
+    fn write_pretty(&self, out: &mut String, indent: usize, depth: usize) {
+        let pad = |out: &mut String, depth: usize| out.push_str(&" ".repeat(indent * depth));
+        match self {
+            Json::Array(items) if !items.is_empty() => {
+                out.push_str("[\n");
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        out.push_str(",\n");
+                    }
+                    pad(out, depth + 1);
+                    item.write_pretty(out, indent, depth + 1);
+                }
+                out.push('\n');
+                pad(out, depth);
+                out.push(']');
+            }
+            Json::Object(fields) if !fields.is_empty() => {
+                out.push_str("{\n");
+                for (i, (k, v)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        out.push_str(",\n");
+                    }
+                    pad(out, depth + 1);
+                    out.push_str(&quote(k));
+                    out.push_str(": ");
+                    v.write_pretty(out, indent, depth + 1);
+                }
+                out.push('\n');
+                pad(out, depth);
+                out.push('}');
+            }
+            json => out.push_str(&json.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Json::Null => write!(f, "null"),
+            Json::Bool(b) => write!(f, "{}", b),
+            Json::Num(n) => write!(f, "{}", n),
+            Json::Str(s) => write!(f, "{}", quote(s)),
+            Json::Array(items) => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            }
+            Json::Object(fields) => {
+                write!(f, "{{")?;
+                for (i, (k, v)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}:{}", quote(k), v)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+pub fn quote(s: &str) -> String {
+    let mut out = String::from('"');
+    for ch in s.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[derive(Debug)]
+pub struct JsonError {
+    pub msg: String,
+    // Offset in chars into the parsed text.
+    pub offset: usize,
+}
+
+impl Error for JsonError {}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at offset {}", self.msg, self.offset)
+    }
+}
+
+pub fn parse(text: &str) -> Result<Json, JsonError> {
+    let mut parser = JsonParser {
+        source: text.chars().collect(),
+        current: 0,
+        depth: 0,
+    };
+    let json = parser.value()?;
+    parser.skip_whitespace();
+    if !parser.is_at_end() {
+        return Err(parser.error("Unexpected trailing characters"));
+    }
+    Ok(json)
+}
+
+// Deeper nesting is refused rather than risking the native stack.
+const MAX_DEPTH: usize = 512;
+
+struct JsonParser {
+    source: Vec<char>,
+    current: usize,
+    // How many arrays and objects enclose the current value.
+    depth: usize,
+}
+
+impl JsonParser {
+    fn is_at_end(&self) -> bool {
+        self.current >= self.source.len()
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.source.get(self.current).copied()
+    }
+
+    fn error(&self, msg: &str) -> JsonError {
+        JsonError {
+            msg: msg.to_string(),
+            offset: self.current,
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(' ' | '\t' | '\n' | '\r')) {
+            self.current += 1;
+        }
+    }
+
+    fn expect(&mut self, ch: char) -> Result<(), JsonError> {
+        if self.peek() == Some(ch) {
+            self.current += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("Expected '{}'", ch)))
+        }
+    }
+
+    fn keyword(&mut self, word: &str, value: Json) -> Result<Json, JsonError> {
+        for ch in word.chars() {
+            if self.peek() != Some(ch) {
+                return Err(self.error(&format!("Invalid literal, expected '{}'", word)));
+            }
+            self.current += 1;
+        }
+        Ok(value)
+    }
+
+    fn value(&mut self) -> Result<Json, JsonError> {
+        self.skip_whitespace();
+        match self.peek() {
+            None => Err(self.error("Unexpected end of input")),
+            Some('n') => self.keyword("null", Json::Null),
+            Some('t') => self.keyword("true", Json::Bool(true)),
+            Some('f') => self.keyword("false", Json::Bool(false)),
+            Some('"') => Ok(Json::Str(self.string()?)),
+            Some('[') => self.nested(Self::array),
+            Some('{') => self.nested(Self::object),
+            Some(c) if c == '-' || c.is_ascii_digit() => self.number(),
+            Some(c) => Err(self.error(&format!("Unexpected character '{}'", c))),
+        }
+    }
+
+    fn nested(
+        &mut self,
+        parse: fn(&mut Self) -> Result<Json, JsonError>,
+    ) -> Result<Json, JsonError> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error("Nesting too deep"));
+        }
+        self.depth += 1;
+        let res = parse(self);
+        self.depth -= 1;
+        res
+    }
+
+    fn array(&mut self) -> Result<Json, JsonError> {
+        self.expect('[')?;
+        let mut items = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(']') {
+            self.current += 1;
+            return Ok(Json::Array(items));
+        }
+        loop {
+            items.push(self.value()?);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => self.current += 1,
+                Some(']') => {
+                    self.current += 1;
+                    return Ok(Json::Array(items));
+                }
+                _ => return Err(self.error("Expected ',' or ']' in array")),
+            }
+        }
+    }
+
+    fn object(&mut self) -> Result<Json, JsonError> {
+        self.expect('{')?;
+        let mut fields = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.current += 1;
+            return Ok(Json::Object(fields));
+        }
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some('"') {
+                return Err(self.error("Expected string key in object"));
+            }
+            let key = self.string()?;
+            self.skip_whitespace();
+            self.expect(':')?;
+            fields.push((key, self.value()?));
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => self.current += 1,
+                Some('}') => {
+                    self.current += 1;
+                    return Ok(Json::Object(fields));
+                }
+                _ => return Err(self.error("Expected ',' or '}' in object")),
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, JsonError> {
+        self.expect('"')?;
+        let mut s = String::new();
+        loop {
+            let Some(ch) = self.peek() else {
+                return Err(self.error("Unterminated string"));
+            };
+            match ch {
+                '"' => {
+                    self.current += 1;
+                    return Ok(s);
+                }
+                '\\' => {
+                    self.current += 1;
+                    s.push(self.escape()?);
+                }
+                c if (c as u32) < 0x20 => {
+                    return Err(self.error("Unescaped control character in string"))
+                }
+                c => {
+                    self.current += 1;
+                    s.push(c);
+                }
+            }
+        }
+    }
+
+    fn escape(&mut self) -> Result<char, JsonError> {
+        let ch = match self.peek() {
+            Some('"') => '"',
+            Some('\\') => '\\',
+            Some('/') => '/',
+            Some('b') => '\u{8}',
+            Some('f') => '\u{c}',
+            Some('n') => '\n',
+            Some('r') => '\r',
+            Some('t') => '\t',
+            Some('u') => {
+                self.current += 1;
+                return self.unicode_escape();
+            }
+            _ => return Err(self.error("Invalid escape sequence")),
+        };
+        self.current += 1;
+        Ok(ch)
+    }
+
+    // Handles `\uXXXX`, including UTF-16 surrogate pairs.
+    fn unicode_escape(&mut self) -> Result<char, JsonError> {
+        let start = self.current;
+        let high = self.hex4()?;
+        let code = if (0xD800..0xDC00).contains(&high) {
+            if self.peek() != Some('\\') || self.source.get(self.current + 1) != Some(&'u') {
+                return Err(self.error("Expected low surrogate after high surrogate"));
+            }
+            self.current += 2;
+            let low = self.hex4()?;
+            if !(0xDC00..0xE000).contains(&low) {
+                return Err(self.error("Invalid low surrogate"));
+            }
+            0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+        } else {
+            high
+        };
+        char::from_u32(code).ok_or(JsonError {
+            msg: "Invalid unicode escape".to_string(),
+            offset: start,
+        })
+    }
+
+    fn hex4(&mut self) -> Result<u32, JsonError> {
+        let mut code = 0;
+        for _ in 0..4 {
+            let Some(digit) = self.peek().and_then(|c| c.to_digit(16)) else {
+                return Err(self.error("Expected four hex digits in unicode escape"));
+            };
+            code = code * 16 + digit;
+            self.current += 1;
+        }
+        Ok(code)
+    }
+
+    fn number(&mut self) -> Result<Json, JsonError> {
+        let start = self.current;
+        if self.peek() == Some('-') {
+            self.current += 1;
+        }
+        match self.peek() {
+            Some('0') => self.current += 1,
+            Some(c) if c.is_ascii_digit() => self.digits(),
+            _ => return Err(self.error("Expected digit")),
+        }
+        if self.peek() == Some('.') {
+            self.current += 1;
+            if !self.peek().is_some_and(|c| c.is_ascii_digit()) {
+                return Err(self.error("Expected digit after decimal point"));
+            }
+            self.digits();
+        }
+        if matches!(self.peek(), Some('e' | 'E')) {
+            self.current += 1;
+            if matches!(self.peek(), Some('+' | '-')) {
+                self.current += 1;
+            }
+            if !self.peek().is_some_and(|c| c.is_ascii_digit()) {
+                return Err(self.error("Expected digit in exponent"));
+            }
+            self.digits();
+        }
+        let text: String = self.source[start..self.current].iter().collect();
+        text.parse::<f64>().map(Json::Num).map_err(|_| JsonError {
+            msg: "Invalid number".to_string(),
+            offset: start,
+        })
+    }
+
+    fn digits(&mut self) {
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.current += 1;
+        }
+    }
+}
@@ -0,0 +1,394 @@
+use crate::builtins::builtins;
+use crate::callable::describe_arity;
+use crate::expr::Expr;
+use crate::object::Object;
+use crate::stmt::{Pattern, Stmt};
+use crate::token::{Token, TokenType};
+
+use std::collections::{HashMap, HashSet};
+
+#[derive(Debug)]
+pub struct Warning {
+    pub line: u32,
+    pub msg: String,
+}
+
+pub fn lint(stmts: &[Stmt]) -> Vec<Warning> {
+    let mut linter = Linter::new(stmts);
+    linter.stmts(stmts);
+    linter.warnings.sort_by_key(|w| w.line);
+    linter.warnings
+}
+
+enum LocalKind {
+    Variable,
+    Parameter,
+    Function,
+}
+
+struct Local {
+    name: Token,
+    kind: LocalKind,
+    used: bool,
+}
+
+struct Linter {
+    warnings: Vec<Warning>,
+    // Mirrors the parser's scopes, so that a variable's resolved depth
+    // points at the scope holding its declaration.
+    scopes: Vec<Vec<Local>>,
+    // The least and most arguments taken by builtins and top-level
+    // functions, for checking calls.
+    arities: HashMap<String, (usize, usize)>,
+    builtin_names: HashSet<&'static str>,
+}
+
+impl Linter {
+    fn new(stmts: &[Stmt]) -> Self {
+        let builtins = builtins();
+        let builtin_names = builtins.iter().map(|(name, _)| *name).collect();
+        let mut arities: HashMap<String, (usize, usize)> = builtins
+            .into_iter()
+            .map(|(name, f)| (name.to_string(), (f.arity(), f.max_arity())))
+            .collect();
+        for stmt in stmts {
+            match stmt {
+                Stmt::Fun { name, params, .. } => {
+                    arities.insert(name.lexeme.clone(), (params.len(), params.len()));
+                }
+                // A global that is also a variable could hold anything.
+                Stmt::VarDecl {
+                    variable: Expr::Variable { name, .. },
+                    ..
+                } => {
+                    arities.remove(&name.lexeme);
+                }
+                _ => {}
+            }
+        }
+        Linter {
+            warnings: Vec::new(),
+            scopes: Vec::new(),
+            arities,
+            builtin_names,
+        }
+    }
+
+    fn warn(&mut self, line: u32, msg: &str) {
+        self.warnings.push(Warning {
+            line,
+            msg: msg.to_string(),
+        });
+    }
+
+    fn begin_scope(&mut self) {
+        self.scopes.push(Vec::new());
+    }
+
+    fn end_scope(&mut self) {
+        let scope = self.scopes.pop().unwrap_or_default();
+        for local in scope.iter().filter(|l| !l.used) {
+            let what = match local.kind {
+                LocalKind::Variable => "local variable",
+                LocalKind::Parameter => "parameter",
+                LocalKind::Function => "local function",
+            };
+            self.warn(
+                local.name.line,
+                &format!("Unused {} '{}'.", what, local.name.lexeme),
+            );
+        }
+    }
+
+    fn declare(&mut self, name: &Token, kind: LocalKind) {
+        if self.is_builtin(&name.lexeme) {
+            self.warn(
+                name.line,
+                &format!("Declaration of '{}' shadows a builtin.", name.lexeme),
+            );
+        }
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.push(Local {
+                name: name.clone(),
+                kind,
+                used: false,
+            });
+        }
+    }
+
+    fn is_builtin(&self, name: &str) -> bool {
+        self.builtin_names.contains(name)
+    }
+
+    fn resolve(&mut self, name: &Token, depth: Option<u32>) {
+        let Some(depth) = depth else {
+            return;
+        };
+        let Some(i) = self.scopes.len().checked_sub(depth as usize + 1) else {
+            return;
+        };
+        if let Some(local) = self.scopes[i]
+            .iter_mut()
+            .rev()
+            .find(|l| l.name.lexeme == name.lexeme)
+        {
+            local.used = true;
+        }
+    }
+
+    fn stmts(&mut self, stmts: &[Stmt]) {
+        let mut returned = false;
+        for (i, stmt) in stmts.iter().enumerate() {
+            if let Stmt::Return { keyword, .. } = stmt {
+                if !returned && !stmts[i + 1..].iter().all(is_trivia) {
+                    self.warn(keyword.line, "Unreachable code after return.");
+                }
+                returned = true;
+            }
+            self.stmt(stmt);
+        }
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) {
+        use Stmt::*;
+        match stmt {
+            BlankLine | Comment { .. } | Verbatim { .. } => {}
+            Block { stmts } => {
+                self.begin_scope();
+                self.stmts(stmts);
+                self.end_scope();
+            }
+            Expr { expr } | Print { expr } | Return { expr, .. } => self.expr(expr),
+            For {
+                init,
+                condition,
+                inc,
+                block,
+            } => {
+                self.begin_scope();
+                if let Some(init) = init {
+                    self.stmt(init);
+                }
+                if let Some(condition) = condition {
+                    self.condition(condition);
+                }
+                if let Some(inc) = inc {
+                    self.expr(inc);
+                }
+                self.stmt(block);
+                self.end_scope();
+            }
+            ForIn {
+                variable,
+                iterable,
+                block,
+            } => {
+                self.begin_scope();
+                self.expr(iterable);
+                self.declare(variable, LocalKind::Variable);
+                self.stmt(block);
+                self.end_scope();
+            }
+            Fun {
+                name, params, body, ..
+            } => {
+                self.declare(name, LocalKind::Function);
+                self.begin_scope();
+                for param in params {
+                    self.declare(param, LocalKind::Parameter);
+                }
+                self.stmts(body);
+                self.end_scope();
+            }
+            If {
+                conditionals,
+                else_block,
+            } => {
+                for (condition, block) in conditionals {
+                    self.condition(condition);
+                    self.stmt(block);
+                }
+                if let Some(block) = else_block {
+                    self.stmt(block);
+                }
+            }
+            Import { alias, names, .. } => {
+                for name in alias.iter().chain(names) {
+                    self.declare(name, LocalKind::Variable);
+                }
+            }
+            Match { value, arms, .. } => {
+                self.expr(value);
+                for arm in arms {
+                    self.begin_scope();
+                    if let Pattern::Binding(name) = &arm.pattern {
+                        self.declare(name, LocalKind::Variable);
+                    }
+                    if let Some(guard) = &arm.guard {
+                        self.condition(guard);
+                    }
+                    self.stmt(&arm.body);
+                    self.end_scope();
+                }
+            }
+            VarDecl { variable, value } => {
+                self.expr(value);
+                if let crate::expr::Expr::Variable { name, .. } = variable {
+                    self.declare(name, LocalKind::Variable);
+                }
+            }
+            While { condition, block } => {
+                self.condition(condition);
+                self.stmt(block);
+            }
+        }
+    }
+
+    fn condition(&mut self, condition: &Expr) {
+        let mut inner = condition;
+        while let Expr::Grouping { expr } = inner {
+            inner = expr;
+        }
+        if let Expr::Assign { variable, .. } = inner {
+            if let Expr::Variable { name, .. } = &**variable {
+                self.warn(
+                    name.line,
+                    &format!("Assignment to '{}' used as a condition.", name.lexeme),
+                );
+            }
+        }
+        self.expr(condition);
+    }
+
+    fn expr(&mut self, expr: &Expr) {
+        use Expr::*;
+        match expr {
+            Assign { value, .. } => self.expr(value),
+            // These read the target as well as writing it.
+            CompoundAssign {
+                variable, value, ..
+            } => {
+                self.expr(variable);
+                self.expr(value);
+            }
+            Binary {
+                left,
+                operator,
+                right,
+            } => {
+                self.comparison(left, operator, right);
+                self.expr(left);
+                self.expr(right);
+            }
+            Call {
+                callee,
+                paren,
+                args,
+            } => {
+                self.call(callee, paren, args.len());
+                self.expr(callee);
+                for arg in args.iter() {
+                    self.expr(arg);
+                }
+            }
+            Conditional {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                self.condition(condition);
+                self.expr(then_branch);
+                self.expr(else_branch);
+            }
+            Get { object, .. } => self.expr(object),
+            Grouping { expr } => self.expr(expr),
+            Increment { variable, .. } => self.expr(variable),
+            Interpolation { parts } => {
+                for part in parts {
+                    self.expr(part);
+                }
+            }
+            Index { object, index, .. } => {
+                self.expr(object);
+                self.expr(index);
+            }
+            Literal { .. } => {}
+            Logic { left, right, .. } => {
+                self.expr(left);
+                self.expr(right);
+            }
+            Range {
+                start, end, step, ..
+            } => {
+                self.expr(start);
+                self.expr(end);
+                if let Some(step) = step {
+                    self.expr(step);
+                }
+            }
+            Unary { right, .. } => self.expr(right),
+            Variable { name, depth } => self.resolve(name, *depth),
+        }
+    }
+
+    fn call(&mut self, callee: &Expr, paren: &Token, arg_count: usize) {
+        let Expr::Variable { name, depth: None } = callee else {
+            return;
+        };
+        if let Some(&(min, max)) = self.arities.get(&name.lexeme) {
+            if arg_count < min || arg_count > max {
+                self.warn(
+                    paren.line,
+                    &format!(
+                        "'{}' expects {} arguments but is called with {}.",
+                        name.lexeme,
+                        describe_arity(min, max),
+                        arg_count
+                    ),
+                );
+            }
+        }
+    }
+
+    // `is_equal` is false for any two values of different types.
+    fn comparison(&mut self, left: &Expr, operator: &Token, right: &Expr) {
+        let (Expr::Literal { value: l, .. }, Expr::Literal { value: r, .. }) = (left, right) else {
+            return;
+        };
+        let (l_type, r_type) = (type_name(l), type_name(r));
+        if l_type == r_type {
+            return;
+        }
+        let result = match operator.ttype {
+            TokenType::EqualEqual => "false",
+            TokenType::BangEqual => "true",
+            _ => return,
+        };
+        self.warn(
+            operator.line,
+            &format!(
+                "Comparison between {} and {} is always {}.",
+                l_type, r_type, result
+            ),
+        );
+    }
+}
+
+fn is_trivia(stmt: &Stmt) -> bool {
+    matches!(
+        stmt,
+        Stmt::BlankLine | Stmt::Comment { .. } | Stmt::Verbatim { .. }
+    )
+}
+
+fn type_name(value: &Object) -> &'static str {
+    match value {
+        Object::Nil => "nil",
+        Object::Str(_) => "a string",
+        Object::Num(_) => "a number",
+        Object::Bool(_) => "a bool",
+        Object::Fun(_) => "a function",
+        Object::Module(_) => "a module",
+        Object::Range { .. } => "a range",
+    }
+}
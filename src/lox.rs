@@ -1,17 +1,24 @@
+use crate::ast_dump::{dump_stmts, dump_tokens};
+use crate::files::FsPolicy;
+use crate::formatter::format_source;
 use crate::interpreter::Interpreter;
+use crate::lint::lint;
 use crate::lox_error::LoxError;
-use crate::object::{stringify_cli_result, Object};
+use crate::object::Object;
 use crate::parser::Parser;
 use crate::scanner::Scanner;
 
 use std::fs;
 use std::io;
 use std::io::Write;
+use std::path::Path;
 use std::process;
 use std::rc::Rc;
 
 const COMPILE_ERROR_CODE: i32 = 65;
 const RUNTIME_ERROR_CODE: i32 = 70;
+// Shared by `fmt --check` and `lint` to signal that a file needs attention.
+const CHECK_FAILED_CODE: i32 = 1;
 
 pub struct Lox {
     interpreter: Interpreter,
@@ -24,12 +31,26 @@ impl Lox {
         }
     }
 
+    pub fn set_fs_policy(&mut self, policy: FsPolicy) {
+        self.interpreter.set_fs_policy(policy);
+    }
+
+    pub fn set_seed(&mut self, seed: u64) {
+        self.interpreter.set_seed(seed);
+    }
+
+    pub fn set_script_args(&mut self, args: Vec<String>) {
+        self.interpreter.set_script_args(args);
+    }
+
     pub fn run_file(&mut self, arg: &str) -> io::Result<()> {
         let contents = fs::read_to_string(arg).expect("Should have been able to read the file");
+        self.interpreter.set_script_path(Path::new(arg));
 
-        match self.run(contents) {
+        match self.run(contents, false) {
             Ok(_) => {}
             Err(err) => match err {
+                LoxError::Exit(code) => process::exit(code),
                 LoxError::Parse => process::exit(COMPILE_ERROR_CODE),
                 LoxError::Runtime => process::exit(RUNTIME_ERROR_CODE),
                 LoxError::Scan => process::exit(COMPILE_ERROR_CODE),
@@ -38,20 +59,91 @@ impl Lox {
         Ok(())
     }
 
+    pub fn dump_tokens(&self, arg: &str) -> io::Result<()> {
+        let contents = fs::read_to_string(arg)?;
+        let mut scanner = Scanner::new(contents);
+        match scanner.scan_tokens() {
+            Ok(tokens) => print!("{}", dump_tokens(&tokens)),
+            Err(_) => process::exit(COMPILE_ERROR_CODE),
+        }
+        Ok(())
+    }
+
+    pub fn dump_ast(&self, arg: &str) -> io::Result<()> {
+        let contents = fs::read_to_string(arg)?;
+        let mut scanner = Scanner::new(contents);
+        let Ok(tokens) = scanner.scan_tokens() else {
+            process::exit(COMPILE_ERROR_CODE);
+        };
+        let mut parser = Parser::new(tokens);
+        match parser.parse() {
+            Ok(stmts) => print!("{}", dump_stmts(&stmts)),
+            Err(_) => process::exit(COMPILE_ERROR_CODE),
+        }
+        Ok(())
+    }
+
+    // Rewrites each file in canonical style or, with `check`, only reports
+    // the files that would change.
+    pub fn format_files(&self, paths: &[String], check: bool) -> io::Result<()> {
+        let mut unformatted = false;
+        for path in paths {
+            let contents = fs::read_to_string(path)?;
+            let Ok(formatted) = format_source(contents.clone()) else {
+                process::exit(COMPILE_ERROR_CODE);
+            };
+            if formatted == contents {
+                continue;
+            }
+            if check {
+                println!("Would reformat {}", path);
+                unformatted = true;
+            } else {
+                fs::write(path, formatted)?;
+            }
+        }
+        if unformatted {
+            process::exit(CHECK_FAILED_CODE);
+        }
+        Ok(())
+    }
+
+    pub fn lint_files(&self, paths: &[String]) -> io::Result<()> {
+        let mut warned = false;
+        for path in paths {
+            let contents = fs::read_to_string(path)?;
+            let mut scanner = Scanner::new(contents);
+            let Ok(tokens) = scanner.scan_tokens() else {
+                process::exit(COMPILE_ERROR_CODE);
+            };
+            let mut parser = Parser::new(tokens);
+            let Ok(stmts) = parser.parse() else {
+                process::exit(COMPILE_ERROR_CODE);
+            };
+            for warning in lint(&stmts) {
+                println!("{} [line {}] Warning: {}", path, warning.line, warning.msg);
+                warned = true;
+            }
+        }
+        if warned {
+            process::exit(CHECK_FAILED_CODE);
+        }
+        Ok(())
+    }
+
     pub fn run_repl(&mut self) -> io::Result<()> {
-        let exit_string = "exit()\n".to_string();
         loop {
             print!("> ");
             io::stdout().flush()?;
             let mut user_input = String::new();
             let stdin = io::stdin();
-            stdin.read_line(&mut user_input)?;
-            match user_input {
-                s if s == exit_string => break,
-                _ => {}
+            // Stop at end of input, as with Ctrl-D.
+            if stdin.read_line(&mut user_input)? == 0 {
+                break;
             }
-            match self.run(user_input) {
-                Ok(obj) => println!("val: {}", stringify_cli_result(&obj)),
+            match self.run(user_input, true) {
+                Ok(obj) => println!("val: {}", obj),
+                Err(LoxError::Exit(code)) => process::exit(code),
                 Err(err) => println!("Exited with error: {}", err),
             }
         }
@@ -59,10 +151,14 @@ impl Lox {
         Ok(())
     }
 
-    fn run(&mut self, source: String) -> Result<Rc<Object>, LoxError> {
+    fn run(&mut self, source: String, repl: bool) -> Result<Rc<Object>, LoxError> {
         let mut scanner = Scanner::new(source);
         let tokens = scanner.scan_tokens()?;
-        let mut parser = Parser::new(tokens);
+        let mut parser = if repl {
+            Parser::for_repl(tokens)
+        } else {
+            Parser::new(tokens)
+        };
         let stmts = parser.parse()?;
         self.interpreter.interpret(stmts)
     }
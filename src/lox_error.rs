@@ -7,6 +7,8 @@ use std::rc::Rc;
 
 #[derive(Debug)]
 pub enum LoxError {
+    // The script called `exit` with this status.
+    Exit(i32),
     Parse,
     Runtime,
     Scan,
@@ -22,14 +24,20 @@ impl fmt::Display for LoxError {
 
 pub fn scerror(line_n: u32, msg: &str) -> ScanError {
     eprintln!("[line {}] Error: {}\n", line_n, msg);
-    ScanError::new(msg)
+    ScanError {
+        msg: msg.to_string(),
+        line: line_n,
+    }
 }
 
 pub fn perror(token: Token, msg: &str) -> ParseError {
     let location = location_for(&token);
     let line_n = token.line;
     eprintln!("[line {}] Error {}: {}\n", line_n, location, msg);
-    ParseError::new(msg)
+    ParseError {
+        msg: msg.to_string(),
+        token: Some(token),
+    }
 }
 
 fn location_for(token: &Token) -> String {
@@ -43,14 +51,7 @@ fn location_for(token: &Token) -> String {
 #[derive(Debug)]
 pub struct ScanError {
     pub msg: String,
-}
-
-impl ScanError {
-    pub fn new(msg: &str) -> Self {
-        ScanError {
-            msg: msg.to_string(),
-        }
-    }
+    pub line: u32,
 }
 
 impl Error for ScanError {}
@@ -64,12 +65,15 @@ impl fmt::Display for ScanError {
 #[derive(Debug)]
 pub struct ParseError {
     pub msg: String,
+    // The token the error was reported at, if it has been reported.
+    pub token: Option<Token>,
 }
 
 impl ParseError {
     pub fn new(msg: &str) -> Self {
         ParseError {
             msg: msg.to_string(),
+            token: None,
         }
     }
 }
@@ -84,6 +88,8 @@ impl fmt::Display for ParseError {
 
 #[derive(Debug)]
 pub enum EvalError {
+    // Unwinds the whole program, like `Return` unwinds a call.
+    Exit(i32),
     Return(Rc<Object>),
     Runtime(String),
 }
@@ -105,7 +111,7 @@ impl EvalError {
 
     pub fn with_context(self, token: Token, expr_str: &str) -> Self {
         match self {
-            EvalError::Return(_) => self,
+            EvalError::Exit(_) | EvalError::Return(_) => self,
             EvalError::Runtime(old_msg) => {
                 let location = expr_str.to_string();
                 let msg = old_msg + &format!("\n[line {}] Error at {}", token.line, location);
@@ -116,7 +122,7 @@ impl EvalError {
 
     pub fn report(&self) {
         match self {
-            EvalError::Return(_) => {}
+            EvalError::Exit(_) | EvalError::Return(_) => {}
             EvalError::Runtime(msg) => eprintln!("{}\n", msg),
         }
     }
@@ -127,6 +133,7 @@ impl Error for EvalError {}
 impl fmt::Display for EvalError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
+            EvalError::Exit(code) => write!(f, "Exit {}", code),
             EvalError::Return(obj) => write!(f, "Return {}", obj),
             EvalError::Runtime(msg) => write!(f, "{}", msg),
         }
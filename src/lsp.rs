@@ -0,0 +1,407 @@
+use crate::builtins::builtins;
+use crate::json::{self, Json};
+use crate::lint::lint;
+use crate::parser::Parser;
+use crate::scanner::{Scanner, KEYWORDS};
+use crate::symbols::{SymbolKind, Symbols};
+use crate::token::Token;
+
+use std::collections::HashMap;
+use std::io;
+use std::io::{BufRead, Write};
+
+const ERROR_SEVERITY: f64 = 1.0;
+const WARNING_SEVERITY: f64 = 2.0;
+const METHOD_NOT_FOUND: f64 = -32601.0;
+const FULL_SYNC: f64 = 1.0;
+
+// Larger messages are refused rather than allocated.
+const MAX_MESSAGE_LEN: usize = 64 << 20;
+
+const FUNCTION_COMPLETION: f64 = 3.0;
+const VARIABLE_COMPLETION: f64 = 6.0;
+const KEYWORD_COMPLETION: f64 = 14.0;
+
+// Serves the Language Server Protocol over stdin and stdout until the
+// client sends `exit`.
+pub fn run() -> io::Result<()> {
+    let mut server = Server {
+        documents: HashMap::new(),
+        shutdown: false,
+    };
+    let stdin = io::stdin();
+    let mut input = stdin.lock();
+    while let Some(msg) = read_message(&mut input)? {
+        let Ok(msg) = json::parse(&msg) else {
+            continue;
+        };
+        if msg.get("method").and_then(Json::as_str) == Some("exit") {
+            break;
+        }
+        server.handle(&msg)?;
+    }
+    if !server.shutdown {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn read_message(input: &mut impl BufRead) -> io::Result<Option<String>> {
+    let mut length = None;
+    loop {
+        let mut header = String::new();
+        if input.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some(value) = header.strip_prefix("Content-Length:") {
+            length = value.trim().parse::<usize>().ok();
+        }
+    }
+    let Some(length) = length else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "Missing Content-Length header.",
+        ));
+    };
+    if length > MAX_MESSAGE_LEN {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "Content-Length exceeds the maximum message size.",
+        ));
+    }
+    let mut body = vec![0; length];
+    input.read_exact(&mut body)?;
+    Ok(Some(String::from_utf8_lossy(&body).into_owned()))
+}
+
+fn send(msg: Json) -> io::Result<()> {
+    let body = msg.to_string();
+    let mut stdout = io::stdout().lock();
+    write!(stdout, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    stdout.flush()
+}
+
+struct Server {
+    documents: HashMap<String, String>,
+    shutdown: bool,
+}
+
+impl Server {
+    fn handle(&mut self, msg: &Json) -> io::Result<()> {
+        let method = msg.get("method").and_then(Json::as_str).unwrap_or("");
+        let params = msg.get("params").unwrap_or(&Json::Null);
+        let uri = params
+            .path(&["textDocument", "uri"])
+            .and_then(Json::as_str)
+            .unwrap_or("")
+            .to_string();
+        let result = match method {
+            "initialize" => capabilities(),
+            "shutdown" => {
+                self.shutdown = true;
+                Json::Null
+            }
+            "textDocument/didOpen" => {
+                if let Some(text) = params
+                    .path(&["textDocument", "text"])
+                    .and_then(Json::as_str)
+                {
+                    self.documents.insert(uri.clone(), text.to_string());
+                }
+                return self.publish_diagnostics(&uri);
+            }
+            "textDocument/didChange" => {
+                let changes = match params.get("contentChanges") {
+                    Some(Json::Array(changes)) => changes.as_slice(),
+                    _ => &[],
+                };
+                if let Some(text) = changes.last().and_then(|c| c.get("text")?.as_str()) {
+                    self.documents.insert(uri.clone(), text.to_string());
+                }
+                return self.publish_diagnostics(&uri);
+            }
+            "textDocument/didClose" => {
+                self.documents.remove(&uri);
+                return send(notification(
+                    "textDocument/publishDiagnostics",
+                    Json::object(vec![
+                        ("uri", Json::str(&uri)),
+                        ("diagnostics", Json::Array(Vec::new())),
+                    ]),
+                ));
+            }
+            "textDocument/definition" => self.definition(&uri, params),
+            "textDocument/references" => self.references(&uri, params),
+            "textDocument/hover" => self.hover(&uri, params),
+            "textDocument/completion" => self.completion(&uri),
+            _ => {
+                // Requests carry an id and need an answer; notifications don't.
+                if let Some(id) = msg.get("id") {
+                    return send(Json::object(vec![
+                        ("jsonrpc", Json::str("2.0")),
+                        ("id", id.clone()),
+                        (
+                            "error",
+                            Json::object(vec![
+                                ("code", Json::Num(METHOD_NOT_FOUND)),
+                                ("message", Json::str(&format!("Unknown method {}", method))),
+                            ]),
+                        ),
+                    ]));
+                }
+                return Ok(());
+            }
+        };
+        match msg.get("id") {
+            Some(id) => send(Json::object(vec![
+                ("jsonrpc", Json::str("2.0")),
+                ("id", id.clone()),
+                ("result", result),
+            ])),
+            None => Ok(()),
+        }
+    }
+
+    fn publish_diagnostics(&self, uri: &str) -> io::Result<()> {
+        let text = self.documents.get(uri).cloned().unwrap_or_default();
+        send(notification(
+            "textDocument/publishDiagnostics",
+            Json::object(vec![
+                ("uri", Json::str(uri)),
+                ("diagnostics", Json::Array(diagnostics(text))),
+            ]),
+        ))
+    }
+
+    fn symbols(&self, uri: &str) -> Option<Symbols> {
+        let text = self.documents.get(uri)?;
+        let tokens = Scanner::new(text.clone()).scan_tokens().ok()?;
+        let stmts = Parser::new(tokens).parse().ok()?;
+        Some(Symbols::build(&stmts))
+    }
+
+    // The symbol under the cursor, if the document is valid Lox.
+    fn symbol_at(&self, uri: &str, params: &Json) -> Option<(Symbols, usize)> {
+        let line = params.path(&["position", "line"])?.as_num()? as u32;
+        let character = params.path(&["position", "character"])?.as_num()? as u32;
+        let column = char_column(self.documents.get(uri)?, line, character);
+        let symbols = self.symbols(uri)?;
+        let symbol = symbols.symbol_at(line + 1, column)?;
+        Some((symbols, symbol))
+    }
+
+    fn definition(&self, uri: &str, params: &Json) -> Json {
+        let text = self.documents.get(uri).map(String::as_str).unwrap_or("");
+        self.symbol_at(uri, params)
+            .and_then(|(symbols, symbol)| symbols.symbols[symbol].decl.clone())
+            .map(|decl| location(uri, text, &decl))
+            .unwrap_or(Json::Null)
+    }
+
+    fn references(&self, uri: &str, params: &Json) -> Json {
+        let Some((symbols, symbol)) = self.symbol_at(uri, params) else {
+            return Json::Null;
+        };
+        let include_decl =
+            params.path(&["context", "includeDeclaration"]) != Some(&Json::Bool(false));
+        let decl = symbols.symbols[symbol].decl.as_ref();
+        let text = self.documents.get(uri).map(String::as_str).unwrap_or("");
+        Json::Array(
+            symbols
+                .references_to(symbol)
+                .filter(|token| include_decl || Some(*token) != decl)
+                .map(|token| location(uri, text, token))
+                .collect(),
+        )
+    }
+
+    fn hover(&self, uri: &str, params: &Json) -> Json {
+        let Some((symbols, symbol)) = self.symbol_at(uri, params) else {
+            return Json::Null;
+        };
+        let symbol = &symbols.symbols[symbol];
+        let value = match &symbol.kind {
+            SymbolKind::Variable => format!("var {}", symbol.name),
+            SymbolKind::Parameter => format!("(parameter) {}", symbol.name),
+            SymbolKind::Function { params } => format!(
+                "fun {}({})\narity: {}",
+                symbol.name,
+                params.join(", "),
+                params.len()
+            ),
+            SymbolKind::Builtin { arity } => {
+                format!("<native fn> {}\narity: {}", symbol.name, arity)
+            }
+        };
+        Json::object(vec![(
+            "contents",
+            Json::object(vec![
+                ("kind", Json::str("plaintext")),
+                ("value", Json::Str(value)),
+            ]),
+        )])
+    }
+
+    fn completion(&self, uri: &str) -> Json {
+        let mut items: Vec<(String, f64)> = KEYWORDS
+            .iter()
+            .map(|k| (k.to_string(), KEYWORD_COMPLETION))
+            .collect();
+        for (name, _) in builtins() {
+            items.push((name.to_string(), FUNCTION_COMPLETION));
+        }
+        if let Some(symbols) = self.symbols(uri) {
+            for symbol in symbols.symbols {
+                let kind = match symbol.kind {
+                    SymbolKind::Function { .. } | SymbolKind::Builtin { .. } => FUNCTION_COMPLETION,
+                    _ => VARIABLE_COMPLETION,
+                };
+                if !items.iter().any(|(label, _)| *label == symbol.name) {
+                    items.push((symbol.name, kind));
+                }
+            }
+        }
+        Json::Array(
+            items
+                .into_iter()
+                .map(|(label, kind)| {
+                    Json::object(vec![("label", Json::Str(label)), ("kind", Json::Num(kind))])
+                })
+                .collect(),
+        )
+    }
+}
+
+fn capabilities() -> Json {
+    Json::object(vec![
+        (
+            "capabilities",
+            Json::object(vec![
+                ("textDocumentSync", Json::Num(FULL_SYNC)),
+                ("definitionProvider", Json::Bool(true)),
+                ("referencesProvider", Json::Bool(true)),
+                ("hoverProvider", Json::Bool(true)),
+                ("completionProvider", Json::object(Vec::new())),
+            ]),
+        ),
+        (
+            "serverInfo",
+            Json::object(vec![("name", Json::str("rlox"))]),
+        ),
+    ])
+}
+
+// Scan and parse errors (which include the parser's scope resolution
+// errors), followed by lint warnings once the document parses.
+fn diagnostics(text: String) -> Vec<Json> {
+    let mut scanner = Scanner::new(text.clone());
+    let Ok(tokens) = scanner.scan_tokens() else {
+        return scanner
+            .errors()
+            .iter()
+            .map(|err| diagnostic(line_range(err.line), ERROR_SEVERITY, &err.msg))
+            .collect();
+    };
+    let mut parser = Parser::new(tokens);
+    match parser.parse() {
+        Ok(stmts) => lint(&stmts)
+            .iter()
+            .map(|w| diagnostic(line_range(w.line), WARNING_SEVERITY, &w.msg))
+            .collect(),
+        Err(_) => parser
+            .errors()
+            .iter()
+            .filter_map(|err| {
+                let token = err.token.as_ref()?;
+                Some(diagnostic(
+                    token_range(&text, token),
+                    ERROR_SEVERITY,
+                    &err.msg,
+                ))
+            })
+            .collect(),
+    }
+}
+
+fn diagnostic(range: Json, severity: f64, msg: &str) -> Json {
+    Json::object(vec![
+        ("range", range),
+        ("severity", Json::Num(severity)),
+        ("source", Json::str("rlox")),
+        ("message", Json::str(msg)),
+    ])
+}
+
+fn notification(method: &str, params: Json) -> Json {
+    Json::object(vec![
+        ("jsonrpc", Json::str("2.0")),
+        ("method", Json::str(method)),
+        ("params", params),
+    ])
+}
+
+fn location(uri: &str, text: &str, token: &Token) -> Json {
+    Json::object(vec![
+        ("uri", Json::str(uri)),
+        ("range", token_range(text, token)),
+    ])
+}
+
+fn position(line: u32, character: u32) -> Json {
+    Json::object(vec![
+        ("line", Json::Num(line as f64)),
+        ("character", Json::Num(character as f64)),
+    ])
+}
+
+// LSP lines are zero-based, while token lines start at one.
+fn token_range(text: &str, token: &Token) -> Json {
+    let line = token.line.saturating_sub(1);
+    let start = utf16_column(text, line, token.column);
+    let end = start + token.lexeme.encode_utf16().count() as u32;
+    Json::object(vec![
+        ("start", position(line, start)),
+        ("end", position(line, end)),
+    ])
+}
+
+// LSP counts columns in UTF-16 code units, while tokens count chars.
+fn utf16_column(text: &str, line: u32, column: u32) -> u32 {
+    match text.lines().nth(line as usize) {
+        Some(l) => l
+            .chars()
+            .take(column as usize)
+            .map(|ch| ch.len_utf16() as u32)
+            .sum(),
+        None => column,
+    }
+}
+
+// The char column at an LSP position, the reverse of `utf16_column`.
+fn char_column(text: &str, line: u32, character: u32) -> u32 {
+    let Some(l) = text.lines().nth(line as usize) else {
+        return character;
+    };
+    let mut units = 0;
+    let mut column = 0;
+    for ch in l.chars() {
+        if units >= character {
+            break;
+        }
+        units += ch.len_utf16() as u32;
+        column += 1;
+    }
+    column
+}
+
+fn line_range(line: u32) -> Json {
+    let line = line.saturating_sub(1);
+    Json::object(vec![
+        ("start", position(line, 0)),
+        ("end", position(line + 1, 0)),
+    ])
+}
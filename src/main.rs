@@ -1,31 +1,107 @@
+mod ast_dump;
 mod builtins;
 mod callable;
 mod environment;
 mod expr;
+mod files;
+mod format;
+mod formatter;
 mod function;
 mod interpreter;
+mod json;
+mod lint;
 mod lox;
 mod lox_error;
+mod lsp;
+mod math;
+mod module;
 mod object;
 mod parser;
+mod printer;
 mod scanner;
 mod stmt;
+mod strings;
+mod symbols;
+mod system;
+mod time;
 mod token;
 
+use files::FsPolicy;
 use lox::Lox;
 
 use std::env;
 use std::process;
 
 fn main() {
-    let args: Vec<_> = env::args().collect();
+    let mut args: Vec<_> = env::args().skip(1).collect();
     let mut lox = Lox::new();
-    let _ = match args.len() {
-        l if l > 2 => {
-            println!("Usage: rlox [script]");
-            process::exit(64);
+    take_run_flags(&mut args, &mut lox);
+    let res = match &args[..] {
+        [] => lox.run_repl(),
+        [cmd, rest @ ..] if cmd == "fmt" => {
+            let check = rest.iter().any(|arg| arg == "--check");
+            let paths: Vec<_> = rest
+                .iter()
+                .filter(|arg| *arg != "--check")
+                .cloned()
+                .collect();
+            if paths.is_empty() {
+                usage();
+            }
+            lox.format_files(&paths, check)
         }
-        2 => lox.run_file(&args[1]),
-        _ => lox.run_repl(),
+        [cmd, paths @ ..] if cmd == "lint" && !paths.is_empty() => lox.lint_files(paths),
+        [cmd] if cmd == "lsp" => lsp::run(),
+        [flag, script] if flag == "--dump-tokens" => lox.dump_tokens(script),
+        [flag, script] if flag == "--dump-ast" => lox.dump_ast(script),
+        [script, script_args @ ..] if !script.starts_with("--") => {
+            lox.set_script_args(script_args.to_vec());
+            lox.run_file(script)
+        }
+        _ => usage(),
     };
+    if let Err(err) = res {
+        eprintln!("{}", err);
+        process::exit(74);
+    }
+}
+
+// Applies the leading `--allow-fs`, `--fs-root <dir>` and `--seed <n>` flags
+// to `lox`, removing them from `args`.
+fn take_run_flags(args: &mut Vec<String>, lox: &mut Lox) {
+    loop {
+        match args.first().map(String::as_str) {
+            Some("--allow-fs") => {
+                lox.set_fs_policy(FsPolicy::Allow);
+                args.remove(0);
+            }
+            Some("--fs-root") if args.len() > 1 => {
+                let dir = args.remove(1);
+                args.remove(0);
+                match FsPolicy::root(&dir) {
+                    Ok(policy) => lox.set_fs_policy(policy),
+                    Err(err) => {
+                        eprintln!("Can't use '{}' as the file root: {}", dir, err);
+                        process::exit(64);
+                    }
+                }
+            }
+            Some("--seed") if args.len() > 1 => {
+                let Ok(seed) = args.remove(1).parse() else {
+                    usage();
+                };
+                args.remove(0);
+                lox.set_seed(seed);
+            }
+            _ => return,
+        }
+    }
+}
+
+fn usage() -> ! {
+    println!("Usage: rlox [--allow-fs | --fs-root <dir>] [--seed <n>] [--dump-tokens | --dump-ast] [script [args...]]");
+    println!("       rlox fmt [--check] <files...>");
+    println!("       rlox lint <files...>");
+    println!("       rlox lsp");
+    process::exit(64);
 }
@@ -0,0 +1,101 @@
+use crate::callable::Callable;
+use crate::interpreter::Interpreter;
+use crate::lox_error::EvalError;
+use crate::object::Object;
+
+use std::f64::consts;
+use std::rc::Rc;
+
+// The contents of the native `math` module.
+pub fn math() -> Vec<(&'static str, Rc<Object>)> {
+    use MathOp::*;
+    let functions = [
+        ("abs", Unary(f64::abs)),
+        ("ceil", Unary(f64::ceil)),
+        ("floor", Unary(f64::floor)),
+        ("round", Unary(f64::round)),
+        ("trunc", Unary(f64::trunc)),
+        ("sqrt", Unary(f64::sqrt)),
+        ("pow", Binary(f64::powf)),
+        ("min", Binary(f64::min)),
+        ("max", Binary(f64::max)),
+        ("sin", Unary(f64::sin)),
+        ("cos", Unary(f64::cos)),
+        ("tan", Unary(f64::tan)),
+        ("asin", Unary(f64::asin)),
+        ("acos", Unary(f64::acos)),
+        ("atan", Unary(f64::atan)),
+        ("atan2", Binary(f64::atan2)),
+        ("exp", Unary(f64::exp)),
+        ("log", Unary(f64::ln)),
+        ("log2", Unary(f64::log2)),
+        ("log10", Unary(f64::log10)),
+        ("is_nan", Predicate(f64::is_nan)),
+        ("is_finite", Predicate(f64::is_finite)),
+    ];
+    let mut names: Vec<(&'static str, Rc<Object>)> = vec![
+        ("PI", Rc::new(Object::Num(consts::PI))),
+        ("E", Rc::new(Object::Num(consts::E))),
+        ("INF", Rc::new(Object::Num(f64::INFINITY))),
+        ("NAN", Rc::new(Object::Num(f64::NAN))),
+    ];
+    for (name, op) in functions {
+        let f: Rc<dyn Callable> = Rc::new(MathFn { name, op });
+        names.push((name, Rc::new(Object::Fun(f))));
+    }
+    names
+}
+
+#[derive(Debug)]
+enum MathOp {
+    Unary(fn(f64) -> f64),
+    Binary(fn(f64, f64) -> f64),
+    Predicate(fn(f64) -> bool),
+}
+
+#[derive(Debug)]
+pub struct MathFn {
+    name: &'static str,
+    op: MathOp,
+}
+
+impl Callable for MathFn {
+    fn arity(&self) -> usize {
+        match self.op {
+            MathOp::Binary(_) => 2,
+            _ => 1,
+        }
+    }
+
+    fn name(&self) -> Option<&str> {
+        Some(self.name)
+    }
+
+    fn call(
+        &self,
+        _interpreter: &mut Interpreter,
+        args: &[Rc<Object>],
+    ) -> Result<Rc<Object>, EvalError> {
+        let res = match self.op {
+            MathOp::Unary(f) => {
+                let Object::Num(n) = &*args[0] else {
+                    return Err(EvalError::new("Expect number."));
+                };
+                Object::Num(f(*n))
+            }
+            MathOp::Binary(f) => {
+                let (Object::Num(a), Object::Num(b)) = (&*args[0], &*args[1]) else {
+                    return Err(EvalError::new("Expect numbers."));
+                };
+                Object::Num(f(*a, *b))
+            }
+            MathOp::Predicate(f) => {
+                let Object::Num(n) = &*args[0] else {
+                    return Err(EvalError::new("Expect number."));
+                };
+                Object::Bool(f(*n))
+            }
+        };
+        Ok(Rc::new(res))
+    }
+}
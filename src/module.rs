@@ -0,0 +1,59 @@
+use crate::builtins::builtins;
+use crate::environment::Environment;
+use crate::math::math;
+use crate::object::Object;
+use crate::strings::string;
+
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+// The top-level namespace of the script being run or of an imported file.
+#[derive(Debug)]
+pub struct Module {
+    pub name: String,
+    // Empty for the REPL, whose imports are relative to the working directory.
+    pub path: PathBuf,
+    pub globals: Rc<RefCell<Environment>>,
+}
+
+impl Module {
+    pub fn new(path: PathBuf) -> Self {
+        let mut globals = Environment::new();
+        for (name, fun) in builtins() {
+            globals.declare(name, Rc::new(Object::Fun(fun)));
+        }
+        let name = match path.file_stem() {
+            Some(stem) => stem.to_string_lossy().into_owned(),
+            None => "main".to_string(),
+        };
+        Module {
+            name,
+            path,
+            globals: Rc::new(RefCell::new(globals)),
+        }
+    }
+
+    // A module implemented in Rust, imported by name instead of by path.
+    pub fn native(name: &str) -> Option<Self> {
+        let names = match name {
+            "math" => math(),
+            "string" => string(),
+            _ => return None,
+        };
+        let mut globals = Environment::new();
+        for (name, value) in names {
+            globals.declare(name, value);
+        }
+        Some(Module {
+            name: name.to_string(),
+            path: PathBuf::new(),
+            globals: Rc::new(RefCell::new(globals)),
+        })
+    }
+
+    // Import paths are relative to the importing file.
+    pub fn resolve(&self, import: &str) -> PathBuf {
+        self.path.parent().unwrap_or(Path::new("")).join(import)
+    }
+}
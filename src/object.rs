@@ -1,4 +1,5 @@
 use crate::callable::Callable;
+use crate::module::Module;
 
 use std::fmt;
 use std::rc::Rc;
@@ -10,6 +11,14 @@ pub enum Object {
     Num(f64),
     Bool(bool),
     Fun(Rc<dyn Callable>),
+    Module(Rc<Module>),
+    // `start..end` or `start..=end`, counting by `step`.
+    Range {
+        start: f64,
+        end: f64,
+        inclusive: bool,
+        step: f64,
+    },
 }
 
 impl fmt::Display for Object {
@@ -21,15 +30,20 @@ impl fmt::Display for Object {
             Num(n) => write!(f, "{}", n),
             Bool(b) => write!(f, "{}", b),
             Fun(fun) => write!(f, "{:?}", fun),
+            Module(module) => write!(f, "<module {}>", module.name),
+            Range {
+                start,
+                end,
+                inclusive,
+                step,
+            } => {
+                let op = if *inclusive { "..=" } else { ".." };
+                write!(f, "{}{}{}", start, op, end)?;
+                if *step != 1.0 {
+                    write!(f, " step {}", step)?;
+                }
+                Ok(())
+            }
         }
     }
 }
-
-pub fn stringify_cli_result(obj: &Object) -> String {
-    let s = format!("{}", obj);
-    if s.ends_with(".0") {
-        s[0..s.len() - 2].to_string()
-    } else {
-        s
-    }
-}
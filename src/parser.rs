@@ -1,10 +1,10 @@
 use crate::expr::Expr;
 use crate::lox_error::{perror, LoxError, ParseError};
 use crate::object::Object;
-use crate::stmt::Stmt;
+use crate::stmt::{MatchArm, Pattern, Stmt};
 use crate::token::{Token, TokenType};
 
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::iter::Peekable;
 use std::rc::Rc;
 use std::vec::IntoIter;
@@ -14,28 +14,93 @@ pub struct Parser {
     prev: Token,
     // This represents all scopes except the global scope.
     scopes: Vec<HashMap<String, bool>>,
+    errors: Vec<ParseError>,
+    // Comments and blank lines are turned into statements only when
+    // keep_trivia is set, so that the formatter can reproduce them.
+    keep_trivia: bool,
+    // Each comment is paired with the number of tokens that precede it.
+    comments: VecDeque<(usize, Token)>,
+    consumed: usize,
+    trivia_line: u32,
+    // Source lines, so that statements whose comments can't be kept in
+    // place are emitted as written instead.
+    source: Vec<String>,
+    // The REPL lets the last expression statement go without a `;`, so
+    // that `exit()` works as typed.
+    repl: bool,
+    // Set when a comment sat between the tokens of a statement.
+    misplaced_comment: bool,
+    // Set when such a statement shares a line with its neighbours, so not
+    // even that is possible.
+    stranded_comment: bool,
 }
 
 impl Parser {
     pub fn new(tokens: Vec<Token>) -> Self {
         let prev = Token::new(TokenType::Sof, "".to_string(), "".to_string(), 0);
+        let mut code = Vec::new();
+        let mut comments = VecDeque::new();
+        for token in tokens {
+            if let TokenType::Comment(_) = token.ttype {
+                comments.push_back((code.len(), token));
+            } else {
+                code.push(token);
+            }
+        }
         Parser {
-            tokens: tokens.into_iter().peekable(),
+            tokens: code.into_iter().peekable(),
             prev,
             scopes: Vec::new(),
+            errors: Vec::new(),
+            keep_trivia: false,
+            comments,
+            consumed: 0,
+            trivia_line: 0,
+            source: Vec::new(),
+            misplaced_comment: false,
+            stranded_comment: false,
+            repl: false,
+        }
+    }
+
+    pub fn for_repl(tokens: Vec<Token>) -> Self {
+        Parser {
+            repl: true,
+            ..Parser::new(tokens)
         }
     }
 
+    pub fn with_trivia(tokens: Vec<Token>, source: &str) -> Self {
+        Parser {
+            keep_trivia: true,
+            source: source.lines().map(String::from).collect(),
+            ..Parser::new(tokens)
+        }
+    }
+
+    pub fn stranded_comment(&self) -> bool {
+        self.stranded_comment
+    }
+
     pub fn parse(&mut self) -> Result<Vec<Stmt>, LoxError> {
         let mut stmts = Vec::new();
         let mut failed = false;
-        while self.tokens.peek().unwrap().ttype != TokenType::Eof {
+        loop {
+            stmts.append(&mut self.trivia());
+            if self.tokens.peek().unwrap().ttype == TokenType::Eof {
+                break;
+            }
+            let start = self.consumed;
+            let start_line = self.next_line();
+            let shares_start = start_line <= self.trivia_line;
+            self.misplaced_comment = false;
             match self.declaration() {
                 Ok(stmt) => {
-                    stmts.push(stmt);
+                    stmts.push(self.keep_comments_in_place(stmt, start, start_line, shares_start));
                 }
-                Err(_) => {
+                Err(err) => {
                     failed = true;
+                    self.record_error(err);
                     let _ = self.synchronize();
                 }
             }
@@ -50,11 +115,24 @@ impl Parser {
         }
     }
 
+    pub fn errors(&self) -> &[ParseError] {
+        &self.errors
+    }
+
+    // Errors without a token only summarize ones that were already recorded.
+    fn record_error(&mut self, err: ParseError) {
+        if err.token.is_some() {
+            self.errors.push(err);
+        }
+    }
+
     fn declaration(&mut self) -> Result<Stmt, ParseError> {
         if self.match_advance(&[TokenType::Fun]) {
             self.fun_statement()
         } else if self.match_advance(&[TokenType::Var]) {
             self.var_statement()
+        } else if self.match_advance(&[TokenType::Import, TokenType::From]) {
+            self.import_statement()
         } else {
             self.statement()
         }
@@ -69,6 +147,8 @@ impl Parser {
             self.if_statement()
         } else if self.match_advance(&[TokenType::While]) {
             self.while_statement()
+        } else if self.match_advance(&[TokenType::Match]) {
+            self.match_statement()
         } else if self.match_advance(&[TokenType::For]) {
             self.for_statement()
         } else if self.match_advance(&[TokenType::Return]) {
@@ -94,11 +174,16 @@ impl Parser {
         let mut failed = false;
 
         if self.match_advance(&[TokenType::LeftBrace]) {
-            while !self.match_advance(&[TokenType::RightBrace]) {
+            loop {
+                stmts.append(&mut self.trivia());
+                if self.match_advance(&[TokenType::RightBrace]) {
+                    break;
+                }
                 match self.declaration() {
                     Ok(stmt) => stmts.push(stmt),
-                    Err(_) => {
+                    Err(err) => {
                         failed = true;
+                        self.record_error(err);
                         let _ = self.synchronize();
                     }
                 }
@@ -126,8 +211,14 @@ impl Parser {
         self.consume(TokenType::LeftParen, "Expect ( for condition.")?;
         let init = if self.match_advance(&[TokenType::Semicolon]) {
             None
+        } else if self.match_advance(&[TokenType::Var]) {
+            let name = self.var_name()?;
+            if self.match_advance(&[TokenType::In]) {
+                return self.for_in_statement(name);
+            }
+            Some(Box::new(self.var_declaration(name)?))
         } else {
-            if !(self.check_var() || self.check_identifier()) {
+            if !self.check_identifier() {
                 return Err(perror(self.peek()?.clone(), "Expect expression."));
             }
             Some(Box::new(self.declaration()?))
@@ -159,6 +250,22 @@ impl Parser {
         })
     }
 
+    // The loop variable lives in the scope pushed by `for_statement`, which
+    // the iterable is resolved in before the variable is declared.
+    fn for_in_statement(&mut self, name: Token) -> Result<Stmt, ParseError> {
+        let iterable = self.expression()?;
+        self.consume(TokenType::RightParen, "Expect ) after for-in iterable.")?;
+        self.declare_var(&name)?;
+        self.define_var(&name)?;
+        let blk = self.block()?;
+        self.scopes.pop();
+        Ok(Stmt::ForIn {
+            variable: name,
+            iterable,
+            block: Box::new(blk),
+        })
+    }
+
     fn fun_statement(&mut self) -> Result<Stmt, ParseError> {
         let name = if self.check_identifier() {
             self.advance()?.clone()
@@ -220,45 +327,93 @@ impl Parser {
     }
 
     fn var_statement(&mut self) -> Result<Stmt, ParseError> {
+        let name = self.var_name()?;
+        self.var_declaration(name)
+    }
+
+    fn var_name(&mut self) -> Result<Token, ParseError> {
         if self.check_identifier() {
-            let vname = self.peek()?.clone();
-            self.declare_var(&vname)?;
-            let (vr, vl) = match self.expression()? {
-                Expr::Assign { variable, value } => {
-                    if let Expr::Variable { ref name, .. } = *value {
-                        if &vname == name && self.depth_for(&vname)?.is_some() {
-                            return Err(perror(
-                                self.peek_prev().clone(),
-                                "Can't read local variable in its own initializer.",
-                            ));
-                        }
-                    }
-                    self.define_var(&vname)?;
-                    (variable, value)
-                }
-                Expr::Variable { name, depth } => {
-                    self.define_var(&name)?;
-                    (
-                        Box::new(Expr::Variable {
-                            name: name.clone(),
-                            depth,
-                        }),
-                        Box::new(Expr::Literal { value: Object::Nil }),
-                    )
+            Ok(self.advance()?.clone())
+        } else {
+            Err(perror(self.peek()?.clone(), "Expect variable name."))
+        }
+    }
+
+    // The rest of a `var` statement, after the name.
+    fn var_declaration(&mut self, name: Token) -> Result<Stmt, ParseError> {
+        self.declare_var(&name)?;
+        let depth = self.depth_for(&name)?;
+        let value = if self.match_advance(&[TokenType::Equal]) {
+            let value = self.expression()?;
+            if let Expr::Variable {
+                name: ref vname,
+                depth: Some(_),
+            } = value
+            {
+                if vname.lexeme == name.lexeme {
+                    return Err(perror(
+                        self.peek_prev().clone(),
+                        "Can't read local variable in its own initializer.",
+                    ));
                 }
-                _ => return Err(perror(self.peek_prev().clone(), "Invalid declaration")),
-            };
-            self.advance_end_of_statement()?;
-            Ok(Stmt::VarDecl {
-                variable: *vr,
-                value: *vl,
-            })
+            }
+            value
         } else {
-            Err(perror(
-                self.tokens.peek().unwrap().clone(),
-                "Expect variable name.",
-            ))
+            Expr::Literal {
+                value: Object::Nil,
+                lexeme: None,
+            }
+        };
+        self.define_var(&name)?;
+        self.advance_end_of_statement()?;
+        Ok(Stmt::VarDecl {
+            variable: Expr::Variable { name, depth },
+            value,
+        })
+    }
+
+    fn import_statement(&mut self) -> Result<Stmt, ParseError> {
+        let keyword = self.peek_prev().clone();
+        let path = match self.advance()?.ttype.clone() {
+            TokenType::StringLit(path) => path,
+            _ => {
+                return Err(perror(
+                    self.peek_prev().clone(),
+                    "Expect module path string.",
+                ))
+            }
+        };
+        let mut alias = None;
+        let mut names = Vec::new();
+        if keyword.ttype == TokenType::Import {
+            self.consume(TokenType::As, "Expect 'as' after module path.")?;
+            alias = Some(self.var_name()?);
+        } else {
+            self.consume(TokenType::Import, "Expect 'import' after module path.")?;
+            loop {
+                names.push(self.var_name()?);
+                if !self.match_advance(&[TokenType::Comma]) {
+                    break;
+                }
+            }
         }
+        for name in alias.iter().chain(&names) {
+            self.declare_var(name)?;
+            self.define_var(name)?;
+        }
+        self.advance_end_of_statement()?;
+        let depth = if self.scopes.is_empty() {
+            None
+        } else {
+            Some(0)
+        };
+        Ok(Stmt::Import {
+            keyword,
+            path,
+            alias,
+            names,
+            depth,
+        })
     }
 
     fn print_statement(&mut self) -> Result<Stmt, ParseError> {
@@ -301,29 +456,204 @@ impl Parser {
         })
     }
 
+    fn match_statement(&mut self) -> Result<Stmt, ParseError> {
+        let keyword = self.peek_prev().clone();
+        self.consume(TokenType::LeftParen, "Expect ( for match value.")?;
+        let value = self.expression()?;
+        self.consume(TokenType::RightParen, "Expect ) for match value.")?;
+        self.consume(TokenType::LeftBrace, "Expect { before match arms.")?;
+        let mut arms = Vec::new();
+        while !self.check(&[TokenType::RightBrace, TokenType::Eof]) {
+            self.scopes.push(HashMap::new());
+            let arm = self.match_arm();
+            self.scopes.pop();
+            arms.push(arm?);
+            self.match_advance(&[TokenType::Comma]);
+        }
+        self.consume(TokenType::RightBrace, "Expect } after match arms.")?;
+        Ok(Stmt::Match {
+            keyword,
+            value,
+            arms,
+        })
+    }
+
+    fn match_arm(&mut self) -> Result<MatchArm, ParseError> {
+        let pattern = self.pattern()?;
+        if let Pattern::Binding(ref name) = pattern {
+            self.define_var(name)?;
+        }
+        let guard = if self.match_advance(&[TokenType::If]) {
+            Some(self.expression()?)
+        } else {
+            None
+        };
+        self.consume(TokenType::EqualGreater, "Expect => after match pattern.")?;
+        let body = self.block()?;
+        Ok(MatchArm {
+            pattern,
+            guard,
+            body: Box::new(body),
+        })
+    }
+
+    fn pattern(&mut self) -> Result<Pattern, ParseError> {
+        if self.check_identifier() {
+            let name = self.advance()?.clone();
+            return Ok(if name.lexeme == "_" {
+                Pattern::Wildcard
+            } else {
+                Pattern::Binding(name)
+            });
+        }
+        let mut literals = vec![self.literal_pattern()?];
+        while self.match_advance(&[TokenType::Pipe]) {
+            literals.push(self.literal_pattern()?);
+        }
+        Ok(Pattern::Literals(literals))
+    }
+
+    // A literal, or a negative number.
+    fn literal_pattern(&mut self) -> Result<Expr, ParseError> {
+        let negative = self.match_advance(&[TokenType::Minus]);
+        let token = self.peek()?.clone();
+        let is_literal = match token.ttype {
+            TokenType::Number(_) => true,
+            TokenType::StringLit(_) | TokenType::True | TokenType::False | TokenType::Nil => {
+                !negative
+            }
+            _ => false,
+        };
+        if !is_literal {
+            return Err(perror(token, "Expect literal, name or _ in match pattern."));
+        }
+        match self.primary()? {
+            Expr::Literal {
+                value: Object::Num(n),
+                lexeme,
+            } if negative => Ok(Expr::Literal {
+                value: Object::Num(-n),
+                lexeme: lexeme.map(|l| format!("-{}", l)),
+            }),
+            literal => Ok(literal),
+        }
+    }
+
     fn return_statement(&mut self) -> Result<Stmt, ParseError> {
+        let keyword = self.peek_prev().clone();
         if self.scopes.is_empty() {
-            return Err(perror(
-                self.peek_prev().clone(),
-                "Can't return from top-level code.",
-            ));
+            return Err(perror(keyword, "Can't return from top-level code."));
         }
         if self.match_advance(&[TokenType::Semicolon]) {
             return Ok(Stmt::Return {
-                expr: Expr::Literal { value: Object::Nil },
+                keyword,
+                expr: Expr::Literal {
+                    value: Object::Nil,
+                    lexeme: None,
+                },
             });
         }
         let expr = self.expression()?;
         self.advance_end_of_statement()?;
-        Ok(Stmt::Return { expr })
+        Ok(Stmt::Return { keyword, expr })
     }
 
     fn expr_statement(&mut self) -> Result<Stmt, ParseError> {
         let expr = self.expression()?;
-        self.advance_end_of_statement()?;
+        if !(self.repl && self.check(&[TokenType::Eof])) {
+            self.advance_end_of_statement()?;
+        }
         Ok(Stmt::Expr { expr })
     }
 
+    // Collects the comments that come before the next token, plus a blank
+    // line wherever the source separates two statements with one.
+    fn trivia(&mut self) -> Vec<Stmt> {
+        let mut trivia = Vec::new();
+        if !self.keep_trivia {
+            return trivia;
+        }
+        let (next_line, at_end) = match self.tokens.peek() {
+            Some(t) => (
+                t.line - t.lexeme.matches('\n').count() as u32,
+                matches!(t.ttype, TokenType::Eof | TokenType::RightBrace),
+            ),
+            None => return trivia,
+        };
+        let at_start = matches!(self.prev.ttype, TokenType::Sof | TokenType::LeftBrace);
+        self.trivia_line = self.trivia_line.max(self.prev.line);
+        while self
+            .comments
+            .front()
+            .is_some_and(|(before, _)| *before <= self.consumed)
+        {
+            let (before, comment) = self.comments.pop_front().unwrap();
+            if before < self.consumed {
+                self.misplaced_comment = true;
+                continue;
+            }
+            let trailing = comment.line == self.prev.line && self.prev.ttype != TokenType::Sof;
+            let first_in_block = at_start && trivia.is_empty();
+            if !(trailing || first_in_block) && comment.line > self.trivia_line + 1 {
+                trivia.push(Stmt::BlankLine);
+            }
+            self.trivia_line = comment.line + comment.lexeme.matches('\n').count() as u32;
+            trivia.push(Stmt::Comment {
+                text: comment.lexeme,
+                trailing,
+            });
+        }
+        let first_in_block = at_start
+            && trivia
+                .iter()
+                .all(|t| matches!(t, Stmt::Comment { trailing: true, .. }));
+        if !(at_end || first_in_block) && next_line > self.trivia_line + 1 {
+            trivia.push(Stmt::BlankLine);
+        }
+        trivia
+    }
+
+    // The line the next token starts on.
+    fn next_line(&mut self) -> u32 {
+        self.tokens
+            .peek()
+            .map(|t| t.line - t.lexeme.matches('\n').count() as u32)
+            .unwrap_or(self.prev.line)
+    }
+
+    // Comments between the tokens of a statement have nowhere to go in the
+    // printed output, so a top-level statement holding any is kept verbatim.
+    fn keep_comments_in_place(
+        &mut self,
+        stmt: Stmt,
+        start: usize,
+        start_line: u32,
+        shares_start: bool,
+    ) -> Stmt {
+        let end_line = self.prev.line;
+        let consumed = self.consumed;
+        let misplaced = self.misplaced_comment
+            || self
+                .comments
+                .iter()
+                .any(|(before, _)| *before > start && *before < consumed);
+        if !self.keep_trivia || !misplaced {
+            return stmt;
+        }
+        // Also drop a trailing comment, which the verbatim lines include.
+        self.comments.retain(|(before, comment)| {
+            *before > consumed || (*before == consumed && comment.line > end_line)
+        });
+        if shares_start || self.next_line() <= end_line {
+            self.stranded_comment = true;
+            return stmt;
+        }
+        let lines = &self.source[start_line as usize - 1..end_line as usize];
+        Stmt::Verbatim {
+            text: lines.join("\n"),
+        }
+    }
+
     fn peek(&mut self) -> Result<&Token, ParseError> {
         if let Some(t) = self.tokens.peek() {
             Ok(t)
@@ -337,7 +667,8 @@ impl Parser {
     }
 
     fn advance(&mut self) -> Result<&Token, ParseError> {
-        if let Some(t) = self.tokens.next().take() {
+        if let Some(t) = self.tokens.next() {
+            self.consumed += 1;
             self.prev = t;
             Ok(&self.prev)
         } else {
@@ -351,7 +682,8 @@ impl Parser {
     fn match_advance(&mut self, matches: &[TokenType]) -> bool {
         let is_match = self.check(matches);
         if is_match {
-            self.prev = self.tokens.next().take().unwrap();
+            self.consumed += 1;
+            self.prev = self.tokens.next().unwrap();
         }
         is_match
     }
@@ -359,14 +691,10 @@ impl Parser {
     fn check(&mut self, matches: &[TokenType]) -> bool {
         self.tokens
             .peek()
-            .map(|t| matches.iter().any(|mtt| *mtt == t.ttype))
+            .map(|t| matches.contains(&t.ttype))
             .unwrap_or(false)
     }
 
-    fn check_var(&mut self) -> bool {
-        self.check(&[TokenType::Var])
-    }
-
     fn check_identifier(&mut self) -> bool {
         if let Some(t) = self.tokens.peek() {
             matches!(t.ttype, TokenType::Identifier(_))
@@ -389,7 +717,27 @@ impl Parser {
     }
 
     fn expression(&mut self) -> Result<Expr, ParseError> {
-        self.logic_or()
+        self.conditional()
+    }
+
+    // Right-associative, so `a ? b : c ? d : e` is `a ? b : (c ? d : e)`.
+    fn conditional(&mut self) -> Result<Expr, ParseError> {
+        let condition = self.logic_or()?;
+
+        if self.match_advance(&[TokenType::Question]) {
+            let then_branch = self.expression()?;
+            self.consume(
+                TokenType::Colon,
+                "Expect ':' after then branch of conditional expression.",
+            )?;
+            let else_branch = self.conditional()?;
+            return Ok(Expr::Conditional {
+                condition: Box::new(condition),
+                then_branch: Box::new(then_branch),
+                else_branch: Box::new(else_branch),
+            });
+        }
+        Ok(condition)
     }
 
     fn logic_or(&mut self) -> Result<Expr, ParseError> {
@@ -426,25 +774,39 @@ impl Parser {
         let mut expr = self.equality()?;
 
         if self.match_advance(&[TokenType::Equal]) {
-            let vname = match expr {
-                Expr::Variable { ref name, .. } => name.clone(),
-                _ => {
-                    return Err(perror(
-                        self.peek_prev().clone(),
-                        "Invalid assignment target.",
-                    ))
-                }
-            };
-            self.assign_var(&vname)?;
+            let equals = self.peek_prev().clone();
+            self.assignment_target(&expr, &equals)?;
             let value = self.expression()?;
             expr = Expr::Assign {
                 variable: Box::new(expr),
                 value: Box::new(value),
             }
+        } else if self.match_advance(&[
+            TokenType::MinusEqual,
+            TokenType::PercentEqual,
+            TokenType::PlusEqual,
+            TokenType::SlashEqual,
+            TokenType::StarEqual,
+        ]) {
+            let operator = self.peek_prev().clone();
+            self.assignment_target(&expr, &operator)?;
+            let value = self.expression()?;
+            expr = Expr::CompoundAssign {
+                variable: Box::new(expr),
+                operator,
+                value: Box::new(value),
+            }
         }
         Ok(expr)
     }
 
+    fn assignment_target(&mut self, expr: &Expr, operator: &Token) -> Result<(), ParseError> {
+        match expr {
+            Expr::Variable { name, .. } => self.assign_var(name),
+            _ => Err(perror(operator.clone(), "Invalid assignment target.")),
+        }
+    }
+
     fn equality(&mut self) -> Result<Expr, ParseError> {
         let mut expr = self.comparison()?;
 
@@ -461,14 +823,103 @@ impl Parser {
     }
 
     fn comparison(&mut self) -> Result<Expr, ParseError> {
-        let mut expr = self.term()?;
+        let mut expr = self.range()?;
 
         while self.match_advance(&[
             TokenType::Greater,
             TokenType::GreaterEqual,
             TokenType::Less,
             TokenType::LessEqual,
+            TokenType::In,
         ]) {
+            let operator = self.peek_prev().clone();
+            let right = self.range()?;
+            expr = Expr::Binary {
+                left: Box::new(expr),
+                operator,
+                right: Box::new(right),
+            };
+        }
+        Ok(expr)
+    }
+
+    // Ranges bind looser than arithmetic, so `0..n + 1` ends at `n + 1`.
+    // `step` is only a keyword right after a range's end.
+    fn range(&mut self) -> Result<Expr, ParseError> {
+        let start = self.bit_or()?;
+
+        if self.match_advance(&[TokenType::DotDot, TokenType::DotDotEqual]) {
+            let operator = self.peek_prev().clone();
+            let end = self.bit_or()?;
+            let step = if self.tokens.peek().is_some_and(
+                |t| matches!(t.ttype, TokenType::Identifier(ref name) if name == "step"),
+            ) {
+                self.advance()?;
+                Some(Box::new(self.bit_or()?))
+            } else {
+                None
+            };
+            return Ok(Expr::Range {
+                start: Box::new(start),
+                operator,
+                end: Box::new(end),
+                step,
+            });
+        }
+        Ok(start)
+    }
+
+    // The bitwise operators bind tighter than comparisons, unlike in C, so
+    // `flags & MASK == 0` means `(flags & MASK) == 0`.
+    fn bit_or(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.bit_xor()?;
+
+        while self.match_advance(&[TokenType::Pipe]) {
+            let operator = self.peek_prev().clone();
+            let right = self.bit_xor()?;
+            expr = Expr::Binary {
+                left: Box::new(expr),
+                operator,
+                right: Box::new(right),
+            };
+        }
+        Ok(expr)
+    }
+
+    fn bit_xor(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.bit_and()?;
+
+        while self.match_advance(&[TokenType::Caret]) {
+            let operator = self.peek_prev().clone();
+            let right = self.bit_and()?;
+            expr = Expr::Binary {
+                left: Box::new(expr),
+                operator,
+                right: Box::new(right),
+            };
+        }
+        Ok(expr)
+    }
+
+    fn bit_and(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.shift()?;
+
+        while self.match_advance(&[TokenType::Ampersand]) {
+            let operator = self.peek_prev().clone();
+            let right = self.shift()?;
+            expr = Expr::Binary {
+                left: Box::new(expr),
+                operator,
+                right: Box::new(right),
+            };
+        }
+        Ok(expr)
+    }
+
+    fn shift(&mut self) -> Result<Expr, ParseError> {
+        let mut expr = self.term()?;
+
+        while self.match_advance(&[TokenType::GreaterGreater, TokenType::LessLess]) {
             let operator = self.peek_prev().clone();
             let right = self.term()?;
             expr = Expr::Binary {
@@ -498,7 +949,12 @@ impl Parser {
     fn factor(&mut self) -> Result<Expr, ParseError> {
         let mut expr = self.unary()?;
 
-        while self.match_advance(&[TokenType::Slash, TokenType::Star]) {
+        while self.match_advance(&[
+            TokenType::Slash,
+            TokenType::Star,
+            TokenType::Percent,
+            TokenType::TildeSlash,
+        ]) {
             let operator = self.peek_prev().clone();
             let right = self.unary()?;
             expr = Expr::Binary {
@@ -511,28 +967,101 @@ impl Parser {
     }
 
     fn unary(&mut self) -> Result<Expr, ParseError> {
-        if self.match_advance(&[TokenType::Bang, TokenType::Minus]) {
+        if self.match_advance(&[TokenType::Bang, TokenType::Minus, TokenType::Tilde]) {
             let operator = self.peek_prev().clone();
             let right = self.unary()?;
             Ok(Expr::Unary {
                 operator,
                 right: Box::new(right),
             })
+        } else if self.match_advance(&[TokenType::PlusPlus, TokenType::MinusMinus]) {
+            let operator = self.peek_prev().clone();
+            let variable = self.unary()?;
+            // `--` before something that can't be assigned to, as in
+            // `--(3)`, is still a double negation.
+            if operator.ttype == TokenType::MinusMinus && !matches!(variable, Expr::Variable { .. })
+            {
+                let minus = Token {
+                    ttype: TokenType::Minus,
+                    lexeme: "-".to_string(),
+                    ..operator
+                };
+                let inner = Token {
+                    column: minus.column + 1,
+                    ..minus.clone()
+                };
+                return Ok(Expr::Unary {
+                    operator: minus,
+                    right: Box::new(Expr::Unary {
+                        operator: inner,
+                        right: Box::new(variable),
+                    }),
+                });
+            }
+            self.assignment_target(&variable, &operator)?;
+            Ok(Expr::Increment {
+                variable: Box::new(variable),
+                operator,
+                postfix: false,
+            })
         } else {
-            Ok(self.call()?)
+            self.exponent()
         }
     }
 
+    // `**` binds tighter than unary minus and is right-associative, so
+    // `-2 ** 2` is -4 and `2 ** 3 ** 2` is 2 ** 9. The right operand may
+    // itself be negated, as in `2 ** -1`.
+    fn exponent(&mut self) -> Result<Expr, ParseError> {
+        let expr = self.call()?;
+
+        if self.match_advance(&[TokenType::StarStar]) {
+            let operator = self.peek_prev().clone();
+            let right = self.unary()?;
+            return Ok(Expr::Binary {
+                left: Box::new(expr),
+                operator,
+                right: Box::new(right),
+            });
+        }
+        Ok(expr)
+    }
+
     fn call(&mut self) -> Result<Expr, ParseError> {
         let mut expr = self.primary()?;
 
         loop {
             if self.match_advance(&[TokenType::LeftParen]) {
                 expr = self.build_call(Rc::new(expr))?;
+            } else if self.match_advance(&[TokenType::Dot]) {
+                if !self.check_identifier() {
+                    return Err(perror(self.peek()?.clone(), "Expect name after '.'."));
+                }
+                expr = Expr::Get {
+                    object: Box::new(expr),
+                    name: self.advance()?.clone(),
+                };
+            } else if self.match_advance(&[TokenType::LeftBracket]) {
+                let index = self.expression()?;
+                self.consume(TokenType::RightBracket, "Expect ] after index.")?;
+                expr = Expr::Index {
+                    object: Box::new(expr),
+                    bracket: self.peek_prev().clone(),
+                    index: Box::new(index),
+                };
             } else {
                 break;
             }
         }
+        if self.match_advance(&[TokenType::PlusPlus, TokenType::MinusMinus]) {
+            let operator = self.peek_prev().clone();
+            self.assignment_target(&expr, &operator)?;
+            expr = Expr::Increment {
+                variable: Box::new(expr),
+                operator,
+                postfix: true,
+            };
+        }
         Ok(expr)
     }
 
@@ -580,6 +1109,7 @@ impl Parser {
         match &token.ttype {
             False => Ok(Expr::Literal {
                 value: Object::Bool(false),
+                lexeme: None,
             }),
             Identifier(_) => {
                 let depth = self.depth_for(token)?;
@@ -595,24 +1125,56 @@ impl Parser {
                     expr: Box::new(expr),
                 })
             }
-            Nil => Ok(Expr::Literal { value: Object::Nil }),
+            Nil => Ok(Expr::Literal {
+                value: Object::Nil,
+                lexeme: None,
+            }),
             Number(n) => Ok(Expr::Literal {
                 value: Object::Num(*n),
+                lexeme: Some(token.lexeme.clone()),
             }),
             StringLit(_) => {
                 let s = self.peek_prev().literal.clone();
                 let s2 = s[0..s.len()].to_string();
                 Ok(Expr::Literal {
                     value: Object::Str(s2),
+                    lexeme: None,
                 })
             }
+            StringInterp(s) => self.interpolation(s.clone()),
             True => Ok(Expr::Literal {
                 value: Object::Bool(true),
+                lexeme: None,
             }),
             _ => Err(perror(self.peek_prev().clone(), "Expect expression.")),
         }
     }
 
+    fn interpolation(&mut self, first: String) -> Result<Expr, ParseError> {
+        let mut parts = vec![Expr::Literal {
+            value: Object::Str(first),
+            lexeme: None,
+        }];
+        loop {
+            parts.push(self.expression()?);
+            let token = self.advance()?.clone();
+            match token.ttype {
+                TokenType::StringInterp(s) => parts.push(Expr::Literal {
+                    value: Object::Str(s),
+                    lexeme: None,
+                }),
+                TokenType::StringLit(s) => {
+                    parts.push(Expr::Literal {
+                        value: Object::Str(s),
+                        lexeme: None,
+                    });
+                    return Ok(Expr::Interpolation { parts });
+                }
+                _ => return Err(perror(token, "Expect '}' after interpolated expression.")),
+            }
+        }
+    }
+
     fn declare_var(&mut self, name: &Token) -> Result<(), ParseError> {
         self.set_var(name, false)
     }
@@ -0,0 +1,536 @@
+use crate::expr::Expr;
+use crate::object::Object;
+use crate::stmt::{Pattern, Stmt};
+
+const INDENT: &str = "    ";
+
+pub fn print_expr(expr: &Expr) -> String {
+    use Expr::*;
+    match expr {
+        Assign { variable, value } => format!("{} = {}", print_expr(variable), print_expr(value)),
+        Binary {
+            left,
+            operator,
+            right,
+        }
+        | Logic {
+            left,
+            operator,
+            right,
+        } => format!(
+            "{} {} {}",
+            print_expr(left),
+            operator.lexeme,
+            print_expr(right)
+        ),
+        Call { callee, args, .. } => {
+            let args: Vec<_> = args.iter().map(print_expr).collect();
+            format!("{}({})", print_expr(callee), args.join(", "))
+        }
+        CompoundAssign {
+            variable,
+            operator,
+            value,
+        } => format!(
+            "{} {} {}",
+            print_expr(variable),
+            operator.lexeme,
+            print_expr(value)
+        ),
+        Conditional {
+            condition,
+            then_branch,
+            else_branch,
+        } => format!(
+            "{} ? {} : {}",
+            print_expr(condition),
+            print_expr(then_branch),
+            print_expr(else_branch)
+        ),
+        Get { object, name } => format!("{}.{}", print_expr(object), name.lexeme),
+        Grouping { expr } => format!("({})", print_expr(expr)),
+        Increment {
+            variable,
+            operator,
+            postfix: true,
+        } => format!("{}{}", print_expr(variable), operator.lexeme),
+        Increment {
+            variable,
+            operator,
+            postfix: false,
+        } => format!("{}{}", operator.lexeme, print_expr(variable)),
+        Index { object, index, .. } => format!("{}[{}]", print_expr(object), print_expr(index)),
+        Interpolation { parts } => {
+            let mut s = "\"".to_string();
+            for (i, part) in parts.iter().enumerate() {
+                match part {
+                    Literal {
+                        value: Object::Str(segment),
+                        ..
+                    } if i % 2 == 0 => s.push_str(&escape(segment)),
+                    _ => s.push_str(&format!("${{{}}}", print_expr(part))),
+                }
+            }
+            s.push('"');
+            s
+        }
+        Literal {
+            value: Object::Num(_),
+            lexeme: Some(lexeme),
+        } => lexeme.clone(),
+        Literal { value, .. } => print_literal(value),
+        Range {
+            start,
+            operator,
+            end,
+            step,
+        } => {
+            let mut s = format!(
+                "{}{}{}",
+                print_expr(start),
+                operator.lexeme,
+                print_expr(end)
+            );
+            if let Some(step) = step {
+                s.push_str(&format!(" step {}", print_expr(step)));
+            }
+            s
+        }
+        Unary { operator, right } => {
+            let right = print_expr(right);
+            // Keeps `- -x` from turning into the decrement `--x`.
+            if operator.lexeme == "-" && right.starts_with('-') {
+                format!("- {}", right)
+            } else {
+                format!("{}{}", operator.lexeme, right)
+            }
+        }
+        Variable { name, .. } => name.lexeme.clone(),
+    }
+}
+
+pub fn print_stmt(stmt: &Stmt) -> String {
+    let mut printer = Printer::new();
+    printer.stmt(stmt);
+    let mut out = printer.finish();
+    out.pop();
+    out
+}
+
+pub fn print_program(stmts: &[Stmt]) -> String {
+    let mut printer = Printer::new();
+    for stmt in stmts {
+        printer.stmt(stmt);
+    }
+    printer.finish()
+}
+
+fn print_pattern(pattern: &Pattern) -> String {
+    match pattern {
+        Pattern::Binding(name) => name.lexeme.clone(),
+        Pattern::Literals(literals) => {
+            let literals: Vec<_> = literals.iter().map(print_expr).collect();
+            literals.join(" | ")
+        }
+        Pattern::Wildcard => "_".to_string(),
+    }
+}
+
+fn print_literal(value: &Object) -> String {
+    match value {
+        Object::Str(s) => format!("\"{}\"", escape(s)),
+        _ => value.to_string(),
+    }
+}
+
+// Newlines are left as they are, since strings may span multiple lines.
+fn escape(s: &str) -> String {
+    let mut out = String::new();
+    let mut chars = s.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '$' if chars.peek() == Some(&'{') => out.push_str("\\$"),
+            '\t' => out.push_str("\\t"),
+            '\r' => out.push_str("\\r"),
+            '\0' => out.push_str("\\0"),
+            '\n' => out.push('\n'),
+            c if c.is_control() => out.push_str(&format!("\\u{{{:x}}}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn is_nil_literal(expr: &Expr) -> bool {
+    matches!(
+        expr,
+        Expr::Literal {
+            value: Object::Nil,
+            ..
+        }
+    )
+}
+
+// Emits statements in canonical Lox style: four-space indentation, braces
+// on the same line as their statement and every branch body in a block.
+pub struct Printer {
+    out: String,
+    level: usize,
+}
+
+impl Printer {
+    pub fn new() -> Self {
+        Printer {
+            out: String::new(),
+            level: 0,
+        }
+    }
+
+    pub fn finish(self) -> String {
+        self.out
+    }
+
+    fn indent(&mut self) {
+        for _ in 0..self.level {
+            self.out.push_str(INDENT);
+        }
+    }
+
+    fn line(&mut self, s: &str) {
+        self.indent();
+        self.out.push_str(s);
+        self.out.push('\n');
+    }
+
+    pub fn stmt(&mut self, stmt: &Stmt) {
+        use Stmt::*;
+        match stmt {
+            BlankLine => self.out.push('\n'),
+            Block { stmts } => {
+                self.indent();
+                self.block(stmts);
+                self.out.push('\n');
+            }
+            Fun {
+                name, params, body, ..
+            } => {
+                let params: Vec<_> = params.iter().map(|p| p.lexeme.clone()).collect();
+                self.indent();
+                self.out
+                    .push_str(&format!("fun {}({}) ", name.lexeme, params.join(", ")));
+                self.block(body);
+                self.out.push('\n');
+            }
+            For {
+                init,
+                condition,
+                inc,
+                block,
+            } => {
+                let mut header = "for (".to_string();
+                match init {
+                    Some(init) => header.push_str(&print_stmt(init)),
+                    None => header.push(';'),
+                }
+                if let Some(condition) = condition {
+                    header.push(' ');
+                    header.push_str(&print_expr(condition));
+                }
+                header.push(';');
+                if let Some(inc) = inc {
+                    header.push(' ');
+                    header.push_str(&print_expr(inc));
+                }
+                header.push_str(") ");
+                self.indent();
+                self.out.push_str(&header);
+                self.body(block);
+                self.out.push('\n');
+            }
+            ForIn {
+                variable,
+                iterable,
+                block,
+            } => {
+                self.indent();
+                self.out.push_str(&format!(
+                    "for (var {} in {}) ",
+                    variable.lexeme,
+                    print_expr(iterable)
+                ));
+                self.body(block);
+                self.out.push('\n');
+            }
+            If {
+                conditionals,
+                else_block,
+            } => {
+                self.indent();
+                self.if_chain(conditionals, else_block, "if");
+                self.out.push('\n');
+            }
+            Comment {
+                text,
+                trailing: true,
+            } => {
+                self.out.pop();
+                self.out.push_str(&format!(" {}\n", text));
+            }
+            Comment { text, .. } => self.line(text),
+            Expr { expr } => self.line(&format!("{};", print_expr(expr))),
+            Import {
+                path, alias, names, ..
+            } => {
+                let path = print_literal(&Object::Str(path.clone()));
+                match alias {
+                    Some(alias) => self.line(&format!("import {} as {};", path, alias.lexeme)),
+                    None => {
+                        let names: Vec<_> = names.iter().map(|n| n.lexeme.clone()).collect();
+                        self.line(&format!("from {} import {};", path, names.join(", ")))
+                    }
+                }
+            }
+            Match { value, arms, .. } => {
+                self.line(&format!("match ({}) {{", print_expr(value)));
+                self.level += 1;
+                for arm in arms {
+                    let mut header = print_pattern(&arm.pattern);
+                    if let Some(guard) = &arm.guard {
+                        header.push_str(&format!(" if {}", print_expr(guard)));
+                    }
+                    self.indent();
+                    self.out.push_str(&format!("{} => ", header));
+                    self.body(&arm.body);
+                    self.out.push('\n');
+                }
+                self.level -= 1;
+                self.line("}");
+            }
+            Print { expr } => self.line(&format!("print {};", print_expr(expr))),
+            // The parser fills in `nil` for `return;` and `var x;`.
+            Return { expr, .. } if is_nil_literal(expr) => self.line("return;"),
+            Return { expr, .. } => self.line(&format!("return {};", print_expr(expr))),
+            VarDecl { variable, value } if is_nil_literal(value) => {
+                self.line(&format!("var {};", print_expr(variable)))
+            }
+            Verbatim { text } => {
+                self.out.push_str(text);
+                self.out.push('\n');
+            }
+            VarDecl { variable, value } => self.line(&format!(
+                "var {} = {};",
+                print_expr(variable),
+                print_expr(value)
+            )),
+            While { condition, block } => {
+                self.indent();
+                self.out
+                    .push_str(&format!("while ({}) ", print_expr(condition)));
+                self.body(block);
+                self.out.push('\n');
+            }
+        }
+    }
+
+    // An `else` whose body is another `if` is printed as a flat `elif` chain.
+    fn if_chain(
+        &mut self,
+        conditionals: &[(Expr, Box<Stmt>)],
+        else_block: &Option<Box<Stmt>>,
+        keyword: &str,
+    ) {
+        let mut keyword = keyword;
+        for (condition, block) in conditionals {
+            self.out
+                .push_str(&format!("{} ({}) ", keyword, print_expr(condition)));
+            self.body(block);
+            self.out.push(' ');
+            keyword = "elif";
+        }
+        match else_block.as_deref() {
+            Some(Stmt::If {
+                conditionals,
+                else_block,
+            }) => self.if_chain(conditionals, else_block, "elif"),
+            Some(block) => {
+                self.out.push_str("else ");
+                self.body(block);
+            }
+            None => {
+                self.out.pop();
+            }
+        }
+    }
+
+    // Branch and loop bodies are always printed as blocks.
+    fn body(&mut self, stmt: &Stmt) {
+        match stmt {
+            Stmt::Block { stmts } => self.block(stmts),
+            _ => self.block(std::slice::from_ref(stmt)),
+        }
+    }
+
+    fn block(&mut self, stmts: &[Stmt]) {
+        if stmts.is_empty() {
+            self.out.push_str("{}");
+            return;
+        }
+        self.out.push_str("{\n");
+        self.level += 1;
+        for stmt in stmts {
+            self.stmt(stmt);
+        }
+        self.level -= 1;
+        self.indent();
+        self.out.push('}');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::print_program;
+    use crate::ast_dump::dump_stmts;
+    use crate::parser::Parser;
+    use crate::scanner::Scanner;
+    use crate::stmt::Stmt;
+
+    fn parse(source: &str) -> Vec<Stmt> {
+        let tokens = Scanner::new(source.to_string()).scan_tokens().unwrap();
+        Parser::new(tokens).parse().unwrap()
+    }
+
+    // Canonical source prints back unchanged, and reparsing the printed
+    // source gives the same tree.
+    fn assert_round_trip(source: &str) {
+        let stmts = parse(source);
+        let printed = print_program(&stmts);
+        assert_eq!(printed, source);
+        assert_eq!(dump_stmts(&parse(&printed)), dump_stmts(&stmts));
+    }
+
+    #[test]
+    fn literals() {
+        assert_round_trip("print nil;\nprint true;\nprint false;\n");
+        assert_round_trip("print 1.5;\nprint 0xff;\nprint 0b1010;\nprint 1_000;\nprint 2e10;\n");
+        assert_round_trip("print \"tab\\t quote\\\" \\\\ dollar\\${x} \\u{1}\";\n");
+    }
+
+    #[test]
+    fn unary_and_binary() {
+        assert_round_trip("print -x;\nprint !x;\nprint ~x;\nprint - -x;\n");
+        assert_round_trip("print 1 + 2 * 3 - 4 / 5;\nprint (1 + 2) * 3;\n");
+        assert_round_trip("print 7 % 3;\nprint 2 ** 3 ** 2;\nprint -2 ** 2;\nprint 7 ~/ 2;\n");
+        assert_round_trip("print 6 & 3 | 1 ^ 2;\nprint 1 << 4 >> 2;\n");
+        assert_round_trip("print a < b == c >= d;\nprint a != b;\n");
+    }
+
+    #[test]
+    fn logic() {
+        assert_round_trip("print a and b or !c;\n");
+    }
+
+    #[test]
+    fn conditional() {
+        assert_round_trip("print a ? b : c ? d : e;\nprint (a ? b : c) + 1;\n");
+    }
+
+    #[test]
+    fn assignment() {
+        assert_round_trip("var a;\nvar b = 1;\na = b = 2;\n");
+    }
+
+    #[test]
+    fn compound_assignment() {
+        assert_round_trip("var a = 1;\na += 2;\na -= 3;\na *= 4;\na /= 5;\na %= 6;\n");
+    }
+
+    #[test]
+    fn increment_and_decrement() {
+        assert_round_trip("var a = 1;\na++;\na--;\n++a;\n--a;\nprint -(--a);\n");
+    }
+
+    #[test]
+    fn double_negation() {
+        assert_round_trip("print - -(3);\n");
+    }
+
+    #[test]
+    fn interpolation() {
+        assert_round_trip(
+            "var name = \"x\";\nprint \"a ${name} b ${1 + 2}\";\nprint \"${name}\";\n",
+        );
+    }
+
+    #[test]
+    fn calls_and_gets() {
+        assert_round_trip("f();\nf(1, g(2), 3);\nprint m.f(1).g;\n");
+    }
+
+    #[test]
+    fn ranges_and_indexing() {
+        assert_round_trip("print 0..10;\nprint 0..=10;\nprint 10..0 step -2;\n");
+        assert_round_trip("print \"abc\"[1];\nprint \"abc\"[0..2];\nprint 3 in 0..10;\n");
+    }
+
+    #[test]
+    fn blocks_and_control_flow() {
+        assert_round_trip("{\n    var a = 1;\n    {\n        print a;\n    }\n}\n");
+        assert_round_trip(
+            "if (a) {\n    print 1;\n} elif (b) {\n    print 2;\n} else {\n    print 3;\n}\n",
+        );
+        assert_round_trip("while (a < 10) {\n    a = a + 1;\n}\n");
+        assert_round_trip("for (var i = 0; i < 10; i = i + 1) {\n    print i;\n}\n");
+        assert_round_trip("for (;;) {\n    print 1;\n}\n");
+    }
+
+    #[test]
+    fn for_in() {
+        assert_round_trip("for (var ch in \"abc\") {\n    print ch;\n}\n");
+        assert_round_trip("for (var i in 0..10 step 2) {\n    print i;\n}\n");
+    }
+
+    #[test]
+    fn functions() {
+        assert_round_trip(
+            "fun add(a, b) {\n    return a + b;\n}\nfun nothing() {\n    return;\n}\nprint add(1, 2);\n",
+        );
+    }
+
+    #[test]
+    fn match_statement() {
+        assert_round_trip(concat!(
+            "match (status) {\n",
+            "    200 | 204 => {\n        print \"ok\";\n    }\n",
+            "    code if code >= 500 => {\n        print code;\n    }\n",
+            "    \"x\" | nil | true => {\n        print 1;\n    }\n",
+            "    _ => {\n        print \"other\";\n    }\n",
+            "}\n",
+        ));
+    }
+
+    #[test]
+    fn comments_and_blank_lines() {
+        let source = concat!(
+            "// leading\n",
+            "var a = 1; // trailing\n",
+            "\n",
+            "fun f(a, /* inside */ b) {\n",
+            "    /* block */\n",
+            "    return a;\n",
+            "}\n",
+        );
+        let tokens = Scanner::with_comments(source.to_string())
+            .scan_tokens()
+            .unwrap();
+        let stmts = Parser::with_trivia(tokens, source).parse().unwrap();
+        assert!(matches!(stmts.last(), Some(Stmt::Verbatim { .. })));
+        assert_eq!(print_program(&stmts), source);
+    }
+
+    #[test]
+    fn imports() {
+        assert_round_trip("import \"lib/util.lox\" as util;\nfrom \"math\" import sqrt, PI;\n");
+    }
+}
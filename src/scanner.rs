@@ -3,9 +3,9 @@ use crate::token::{Token, TokenType};
 
 use std::mem;
 
-const KEYWORDS: [&str; 17] = [
-    "and", "class", "elif", "else", "false", "fun", "for", "if", "nil", "or", "print", "return",
-    "super", "this", "true", "var", "while",
+pub const KEYWORDS: [&str; 22] = [
+    "and", "as", "class", "elif", "else", "false", "from", "fun", "for", "if", "import", "in",
+    "match", "nil", "or", "print", "return", "super", "this", "true", "var", "while",
 ];
 
 pub struct Scanner {
@@ -14,6 +14,15 @@ pub struct Scanner {
     start: usize,
     current: usize,
     line: u32,
+    // Index of the first character on the current line, for columns.
+    line_start: usize,
+    start_column: u32,
+    errors: Vec<ScanError>,
+    // The brace depth inside each `${...}` that is currently open, so that
+    // the `}` closing it resumes the surrounding string.
+    interpolations: Vec<usize>,
+    // Comments are dropped unless they are needed, e.g. by the formatter.
+    keep_comments: bool,
 }
 
 impl Scanner {
@@ -28,26 +37,45 @@ impl Scanner {
             start,
             current,
             line,
+            line_start: 0,
+            start_column: 0,
+            errors: Vec::new(),
+            interpolations: Vec::new(),
+            keep_comments: false,
+        }
+    }
+
+    pub fn with_comments(s: String) -> Self {
+        Scanner {
+            keep_comments: true,
+            ..Scanner::new(s)
         }
     }
 
     pub fn scan_tokens(&mut self) -> Result<Vec<Token>, LoxError> {
         while !self.is_at_end() {
             self.start = self.current;
-            if self.scan_token().is_err() {
+            self.start_column = (self.start - self.line_start) as u32;
+            if let Err(err) = self.scan_token() {
+                self.errors.push(err);
                 return Err(LoxError::Scan);
             }
         }
+        if !self.interpolations.is_empty() {
+            let err = self.report_error("Unterminated string interpolation.");
+            self.errors.push(err);
+            return Err(LoxError::Scan);
+        }
 
-        self.tokens.push(Token::new(
-            TokenType::Eof,
-            "".to_string(),
-            "".to_string(),
-            self.line,
-        ));
+        self.start_column = (self.current - self.line_start) as u32;
+        self.push_token(TokenType::Eof, "".to_string(), self.line);
         Ok(mem::take(&mut self.tokens))
     }
 
+    pub fn errors(&self) -> &[ScanError] {
+        &self.errors
+    }
+
     fn is_at_end(&self) -> bool {
         self.current >= self.source.len()
     }
@@ -62,8 +90,20 @@ impl Scanner {
         self.source[self.current]
     }
 
+    fn peek_or_end(&self) -> char {
+        if self.is_at_end() {
+            '\0'
+        } else {
+            self.peek()
+        }
+    }
+
     fn peek_next(&self) -> char {
-        self.source[self.current + 1]
+        if self.current + 1 >= self.source.len() {
+            '\0'
+        } else {
+            self.source[self.current + 1]
+        }
     }
 
     fn match_advance(&mut self, m: char) -> bool {
@@ -78,6 +118,11 @@ impl Scanner {
         }
     }
 
+    fn newline(&mut self) {
+        self.line += 1;
+        self.line_start = self.current;
+    }
+
     fn report_error(&mut self, msg: &str) -> ScanError {
         scerror(self.line, msg)
     }
@@ -87,17 +132,89 @@ impl Scanner {
         let token_type = match ch {
             '(' => TokenType::LeftParen,
             ')' => TokenType::RightParen,
-            '{' => TokenType::LeftBrace,
-            '}' => TokenType::RightBrace,
+            '{' => {
+                if let Some(depth) = self.interpolations.last_mut() {
+                    *depth += 1;
+                }
+                TokenType::LeftBrace
+            }
+            '}' => match self.interpolations.last_mut() {
+                Some(0) => {
+                    self.interpolations.pop();
+                    let (t, s) = self.scan_string()?;
+                    self.push_token(t, s, self.line);
+                    return Ok(());
+                }
+                Some(depth) => {
+                    *depth -= 1;
+                    TokenType::RightBrace
+                }
+                None => TokenType::RightBrace,
+            },
             ',' => TokenType::Comma,
-            '.' => TokenType::Dot,
-            '-' => TokenType::Minus,
-            '+' => TokenType::Plus,
+            '[' => TokenType::LeftBracket,
+            ']' => TokenType::RightBracket,
+            '.' => {
+                if !self.match_advance('.') {
+                    TokenType::Dot
+                } else if self.match_advance('=') {
+                    TokenType::DotDotEqual
+                } else {
+                    TokenType::DotDot
+                }
+            }
+            '-' => {
+                if self.match_advance('-') {
+                    TokenType::MinusMinus
+                } else if self.match_advance('=') {
+                    TokenType::MinusEqual
+                } else {
+                    TokenType::Minus
+                }
+            }
+            '+' => {
+                if self.match_advance('+') {
+                    TokenType::PlusPlus
+                } else if self.match_advance('=') {
+                    TokenType::PlusEqual
+                } else {
+                    TokenType::Plus
+                }
+            }
             ';' => TokenType::Semicolon,
-            '*' => TokenType::Star,
+            '?' => TokenType::Question,
+            ':' => TokenType::Colon,
+            '%' => {
+                if self.match_advance('=') {
+                    TokenType::PercentEqual
+                } else {
+                    TokenType::Percent
+                }
+            }
+            '*' => {
+                if self.match_advance('*') {
+                    TokenType::StarStar
+                } else if self.match_advance('=') {
+                    TokenType::StarEqual
+                } else {
+                    TokenType::Star
+                }
+            }
+            '&' => TokenType::Ampersand,
+            '|' => TokenType::Pipe,
+            '^' => TokenType::Caret,
+            '~' => {
+                if self.match_advance('/') {
+                    TokenType::TildeSlash
+                } else {
+                    TokenType::Tilde
+                }
+            }
             '>' => {
                 if self.match_advance('=') {
                     TokenType::GreaterEqual
+                } else if self.match_advance('>') {
+                    TokenType::GreaterGreater
                 } else {
                     TokenType::Greater
                 }
@@ -105,6 +222,8 @@ impl Scanner {
             '<' => {
                 if self.match_advance('=') {
                     TokenType::LessEqual
+                } else if self.match_advance('<') {
+                    TokenType::LessLess
                 } else {
                     TokenType::Less
                 }
@@ -112,6 +231,8 @@ impl Scanner {
             '=' => {
                 if self.match_advance('=') {
                     TokenType::EqualEqual
+                } else if self.match_advance('>') {
+                    TokenType::EqualGreater
                 } else {
                     TokenType::Equal
                 }
@@ -128,31 +249,39 @@ impl Scanner {
                     while !(self.is_at_end() || self.peek() == '\n') {
                         self.current += 1;
                     }
+                    self.add_comment(self.line);
                     return Ok(());
                 } else if self.match_advance('*') {
+                    let start_line = self.line;
                     while !(self.is_at_end() || (self.peek() == '*' && self.peek_next() == '/')) {
                         self.current += 1;
+                        if self.source[self.current - 1] == '\n' {
+                            self.newline();
+                        }
                     }
                     if self.is_at_end() {
                         return Err(self.report_error("You must close multiline comments with */"));
                     } else {
                         self.current += 2;
                     }
+                    self.add_comment(start_line);
                     return Ok(());
+                } else if self.match_advance('=') {
+                    TokenType::SlashEqual
                 } else {
                     TokenType::Slash
                 }
             }
             '"' => {
                 let (t, s) = self.scan_string()?;
-                self.tokens.push(Token::new(t, s.clone(), s, self.line));
+                self.push_token(t, s, self.line);
                 return Ok(());
             }
-            a if a.is_alphabetic() => self.scan_word()?,
+            a if a.is_alphabetic() || a == '_' => self.scan_word()?,
             d if d.is_ascii_digit() => self.scan_number()?,
             ' ' | '\r' | '\t' => return Ok(()),
             '\n' => {
-                self.line += 1;
+                self.newline();
                 return Ok(());
             }
             _ => return Err(self.report_error("Unexpected character.")),
@@ -161,22 +290,72 @@ impl Scanner {
         Ok(())
     }
 
+    // Scans up to the closing quote or, for an interpolated string, up to
+    // the next `${`, which is returned as a StringInterp segment.
     fn scan_string(&mut self) -> Result<(TokenType, String), ScanError> {
         let mut s = "".to_string();
         while !self.match_advance('"') {
             if self.is_at_end() {
                 return Err(self.report_error("Unterminated string."));
             }
-            if self.peek() == '\n' {
-                self.line += 1;
+            if self.peek() == '$' && self.peek_next() == '{' {
+                self.current += 2;
+                self.interpolations.push(0);
+                return Ok((TokenType::StringInterp(s.clone()), s));
             }
             // Add one by one so that Unicode can also be handled correctly.
-            s.push(self.peek());
-            self.current += 1;
+            let ch = self.advance();
+            match ch {
+                '\\' => s.push(self.scan_escape()?),
+                '\n' => {
+                    s.push(ch);
+                    self.newline();
+                }
+                _ => s.push(ch),
+            }
         }
         Ok((TokenType::StringLit(s.clone()), s))
     }
 
+    // Called just after the backslash of an escape sequence.
+    fn scan_escape(&mut self) -> Result<char, ScanError> {
+        if self.is_at_end() {
+            return Err(self.report_error("Unterminated string."));
+        }
+        Ok(match self.advance() {
+            'n' => '\n',
+            't' => '\t',
+            'r' => '\r',
+            '0' => '\0',
+            '"' => '"',
+            '\\' => '\\',
+            '$' => '$',
+            'u' => self.scan_unicode_escape()?,
+            c => return Err(self.report_error(&format!("Invalid escape sequence '\\{}'.", c))),
+        })
+    }
+
+    // Handles the `{XXXX}` part of `\u{XXXX}`, with one to six hex digits.
+    fn scan_unicode_escape(&mut self) -> Result<char, ScanError> {
+        if !self.match_advance('{') {
+            return Err(self.report_error("Expect '{' after '\\u'."));
+        }
+        let mut digits = String::new();
+        while !self.is_at_end() && self.peek().is_ascii_hexdigit() {
+            digits.push(self.advance());
+        }
+        if !self.match_advance('}') {
+            return Err(self.report_error("Expect '}' to close unicode escape."));
+        }
+        if digits.is_empty() || digits.len() > 6 {
+            return Err(self.report_error("Unicode escape must have 1 to 6 hex digits."));
+        }
+        u32::from_str_radix(&digits, 16)
+            .ok()
+            .and_then(char::from_u32)
+            .ok_or_else(|| self.report_error(&format!("Invalid unicode code point '{}'.", digits)))
+    }
+
     fn scan_word(&mut self) -> Result<TokenType, ScanError> {
         while !self.is_at_end() && (self.peek().is_alphanumeric() || self.peek() == '_') {
             self.current += 1;
@@ -189,25 +368,97 @@ impl Scanner {
         })
     }
 
+    // Handles decimal numbers with an optional fraction and exponent, plus
+    // `0x` hex and `0b` binary integers. `_` may separate digits in all of them.
     fn scan_number(&mut self) -> Result<TokenType, ScanError> {
-        while !self.is_at_end() && (self.peek().is_ascii_digit()) {
-            self.current += 1
-        }
-        if self.match_advance('.') {
-            if !self.peek().is_ascii_digit() {
+        let first = self.source[self.start];
+        let radix = match self.peek_or_end() {
+            'x' | 'X' if first == '0' => 16,
+            'b' | 'B' if first == '0' => 2,
+            _ => 10,
+        };
+        let n = if radix == 10 {
+            let mut digits = first.to_string();
+            digits.push_str(&self.scan_digits(10, true)?);
+            if self.peek_or_end() == '.' && self.peek_next().is_ascii_digit() {
+                self.current += 1;
+                digits.push('.');
+                digits.push_str(&self.scan_digits(10, false)?);
+            } else if self.peek_or_end() == '.' && self.peek_next() != '.' {
                 return Err(self.report_error("Number has trailing ."));
             }
-            while self.peek().is_ascii_digit() {
+            if matches!(self.peek_or_end(), 'e' | 'E') {
                 self.current += 1;
+                digits.push('e');
+                if matches!(self.peek_or_end(), '+' | '-') {
+                    digits.push(self.advance());
+                }
+                if !self.peek_or_end().is_ascii_digit() {
+                    return Err(self.report_error("Expect digits in number exponent."));
+                }
+                digits.push_str(&self.scan_digits(10, false)?);
+            }
+            digits.parse::<f64>().unwrap()
+        } else {
+            self.current += 1;
+            let digits = self.scan_digits(radix, true)?;
+            if digits.is_empty() {
+                return Err(self.report_error("Expect digits after number prefix."));
             }
+            match u64::from_str_radix(&digits, radix) {
+                Ok(n) => n as f64,
+                Err(_) => return Err(self.report_error("Number literal is too large.")),
+            }
+        };
+        if self.peek_or_end().is_alphanumeric() || self.peek_or_end() == '_' {
+            return Err(self.report_error("Invalid character in number literal."));
         }
-        let n = self.source_substr().parse::<f64>().unwrap();
         Ok(TokenType::Number(n))
     }
 
+    // Consumes digits of the given radix and the `_` separators between
+    // them, returning just the digits. A leading `_` is only allowed where
+    // it follows a digit or a radix prefix.
+    fn scan_digits(&mut self, radix: u32, leading_separator: bool) -> Result<String, ScanError> {
+        let mut digits = String::new();
+        let mut last_was_separator = false;
+        loop {
+            let ch = self.peek_or_end();
+            if ch == '_' {
+                if last_was_separator || (digits.is_empty() && !leading_separator) {
+                    return Err(self.report_error("Misplaced '_' in number literal."));
+                }
+                last_was_separator = true;
+            } else if ch.is_digit(radix) {
+                digits.push(ch);
+                last_was_separator = false;
+            } else {
+                break;
+            }
+            self.current += 1;
+        }
+        if last_was_separator {
+            return Err(self.report_error("Number literal can't end with '_'."));
+        }
+        Ok(digits)
+    }
+
     fn add_token(&mut self, ttype: TokenType) {
         let s = self.source_substr();
-        self.tokens.push(Token::new(ttype, s.clone(), s, self.line));
+        self.push_token(ttype, s, self.line);
+    }
+
+    fn push_token(&mut self, ttype: TokenType, s: String, line: u32) {
+        let mut token = Token::new(ttype, s.clone(), s, line);
+        token.column = self.start_column;
+        self.tokens.push(token);
+    }
+
+    fn add_comment(&mut self, line: u32) {
+        if self.keep_comments {
+            let s = self.source_substr();
+            self.push_token(TokenType::Comment(s.clone()), s, line);
+        }
     }
 
     fn source_substr(&self) -> String {
@@ -221,13 +472,18 @@ impl Scanner {
     fn keyword_token(&mut self, keyword: &str) -> Result<TokenType, ScanError> {
         Ok(match keyword {
             "and" => TokenType::And,
+            "as" => TokenType::As,
             "class" => TokenType::Class,
             "elif" => TokenType::Elif,
             "else" => TokenType::Else,
             "false" => TokenType::False,
             "fun" => TokenType::Fun,
             "for" => TokenType::For,
+            "from" => TokenType::From,
             "if" => TokenType::If,
+            "import" => TokenType::Import,
+            "in" => TokenType::In,
+            "match" => TokenType::Match,
             "nil" => TokenType::Nil,
             "or" => TokenType::Or,
             "print" => TokenType::Print,
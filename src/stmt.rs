@@ -1,4 +1,5 @@
 use crate::expr::Expr;
+use crate::printer::print_stmt;
 use crate::token::Token;
 use std::rc::Rc;
 
@@ -6,9 +7,15 @@ use std::fmt;
 
 #[derive(Debug)]
 pub enum Stmt {
+    // Only produced by Parser::with_trivia, for the formatter.
+    BlankLine,
     Block {
         stmts: Vec<Stmt>,
     },
+    Comment {
+        text: String,
+        trailing: bool,
+    },
     Expr {
         expr: Expr,
     },
@@ -18,6 +25,12 @@ pub enum Stmt {
         inc: Option<Expr>,
         block: Box<Stmt>,
     },
+    // `for (var x in iterable)`, with a fresh `x` for each iteration.
+    ForIn {
+        variable: Token,
+        iterable: Expr,
+        block: Box<Stmt>,
+    },
     Fun {
         name: Token,
         params: Vec<Token>,
@@ -28,25 +41,61 @@ pub enum Stmt {
         conditionals: Vec<(Expr, Box<Stmt>)>,
         else_block: Option<Box<Stmt>>,
     },
+    // `import "path" as alias;` binds the module to `alias`, while
+    // `from "path" import a, b;` binds the listed names.
+    Import {
+        keyword: Token,
+        path: String,
+        alias: Option<Token>,
+        names: Vec<Token>,
+        depth: Option<u32>,
+    },
+    Match {
+        keyword: Token,
+        value: Expr,
+        arms: Vec<MatchArm>,
+    },
     Print {
         expr: Expr,
     },
     Return {
+        keyword: Token,
         expr: Expr,
     },
     VarDecl {
         variable: Expr,
         value: Expr,
     },
+    // A statement the formatter leaves as written, see Parser::with_trivia.
+    Verbatim {
+        text: String,
+    },
     While {
         condition: Expr,
         block: Box<Stmt>,
     },
 }
 
+// Each arm has its own scope, holding the binding if there is one.
+#[derive(Debug)]
+pub struct MatchArm {
+    pub pattern: Pattern,
+    pub guard: Option<Expr>,
+    pub body: Box<Stmt>,
+}
+
+#[derive(Debug)]
+pub enum Pattern {
+    // Matches anything, binding it to the name.
+    Binding(Token),
+    // Literals separated by `|`, compared with `is_equal`.
+    Literals(Vec<Expr>),
+    // `_`
+    Wildcard,
+}
+
 impl fmt::Display for Stmt {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let s = format!("{:?}", self);
-        write!(f, "{}", s)
+        write!(f, "{}", print_stmt(self))
     }
 }
@@ -0,0 +1,241 @@
+use crate::callable::Callable;
+use crate::interpreter::Interpreter;
+use crate::lox_error::EvalError;
+use crate::object::Object;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+// The longest string, in bytes, that `repeat` will build.
+const MAX_REPEAT_LEN: usize = 1 << 28;
+
+type StringOp = fn(&mut Interpreter, &[Rc<Object>]) -> Result<Object, EvalError>;
+
+// The contents of the native `string` module. Lengths and positions count
+// chars, the same way the scanner walks source code.
+pub fn string() -> Vec<(&'static str, Rc<Object>)> {
+    let functions: [(&'static str, usize, StringOp); 18] = [
+        ("len", 1, len),
+        ("substr", 3, substr),
+        ("index_of", 2, index_of),
+        ("contains", 2, contains),
+        ("starts_with", 2, starts_with),
+        ("ends_with", 2, ends_with),
+        ("split", 2, split),
+        ("join", 2, join),
+        ("trim", 1, trim),
+        ("upper", 1, upper),
+        ("lower", 1, lower),
+        ("replace", 3, replace),
+        ("repeat", 2, repeat),
+        ("chars", 1, chars),
+        ("ord", 1, ord),
+        ("chr", 1, chr),
+        ("trim_start", 1, trim_start),
+        ("trim_end", 1, trim_end),
+    ];
+    functions
+        .into_iter()
+        .map(|(name, arity, op)| {
+            let f: Rc<dyn Callable> = Rc::new(StringFn { name, arity, op });
+            (name, Rc::new(Object::Fun(f)))
+        })
+        .collect()
+}
+
+pub struct StringFn {
+    name: &'static str,
+    arity: usize,
+    op: StringOp,
+}
+
+impl Callable for StringFn {
+    fn arity(&self) -> usize {
+        self.arity
+    }
+
+    fn name(&self) -> Option<&str> {
+        Some(self.name)
+    }
+
+    fn call(
+        &self,
+        interpreter: &mut Interpreter,
+        args: &[Rc<Object>],
+    ) -> Result<Rc<Object>, EvalError> {
+        Ok(Rc::new((self.op)(interpreter, args)?))
+    }
+}
+
+// Returns the items one at a time, then nil, so that it works with `for-in`.
+pub struct ItemsFn {
+    items: RefCell<std::vec::IntoIter<Rc<Object>>>,
+}
+
+impl ItemsFn {
+    pub fn new(items: Vec<Rc<Object>>) -> Self {
+        ItemsFn {
+            items: RefCell::new(items.into_iter()),
+        }
+    }
+}
+
+impl Callable for ItemsFn {
+    fn arity(&self) -> usize {
+        0
+    }
+
+    fn call(
+        &self,
+        _interpreter: &mut Interpreter,
+        _args: &[Rc<Object>],
+    ) -> Result<Rc<Object>, EvalError> {
+        Ok(self
+            .items
+            .borrow_mut()
+            .next()
+            .unwrap_or_else(|| Rc::new(Object::Nil)))
+    }
+}
+
+fn str_arg(args: &[Rc<Object>], i: usize) -> Result<&str, EvalError> {
+    match &*args[i] {
+        Object::Str(s) => Ok(s),
+        _ => Err(EvalError::new("Expect string.")),
+    }
+}
+
+fn int_arg(args: &[Rc<Object>], i: usize) -> Result<i64, EvalError> {
+    match &*args[i] {
+        Object::Num(n) if n.fract() == 0.0 => Ok(*n as i64),
+        _ => Err(EvalError::new("Expect integer.")),
+    }
+}
+
+fn items_fn<T: ToString>(items: impl Iterator<Item = T>) -> Object {
+    let items = items
+        .map(|item| Rc::new(Object::Str(item.to_string())))
+        .collect();
+    Object::Fun(Rc::new(ItemsFn::new(items)))
+}
+
+fn len(_: &mut Interpreter, args: &[Rc<Object>]) -> Result<Object, EvalError> {
+    Ok(Object::Num(str_arg(args, 0)?.chars().count() as f64))
+}
+
+// The chars from `start` up to, but not including, `end`.
+fn substr(_: &mut Interpreter, args: &[Rc<Object>]) -> Result<Object, EvalError> {
+    let chars: Vec<char> = str_arg(args, 0)?.chars().collect();
+    let (start, end) = (int_arg(args, 1)?, int_arg(args, 2)?);
+    if start < 0 || end < start || end as usize > chars.len() {
+        return Err(EvalError::new("Substring out of range."));
+    }
+    Ok(Object::Str(
+        chars[start as usize..end as usize].iter().collect(),
+    ))
+}
+
+// The char position of the first occurrence of `sub`, or -1.
+fn index_of(_: &mut Interpreter, args: &[Rc<Object>]) -> Result<Object, EvalError> {
+    let (s, sub) = (str_arg(args, 0)?, str_arg(args, 1)?);
+    let index = match s.find(sub) {
+        Some(byte) => s[..byte].chars().count() as f64,
+        None => -1.0,
+    };
+    Ok(Object::Num(index))
+}
+
+fn contains(_: &mut Interpreter, args: &[Rc<Object>]) -> Result<Object, EvalError> {
+    Ok(Object::Bool(str_arg(args, 0)?.contains(str_arg(args, 1)?)))
+}
+
+fn starts_with(_: &mut Interpreter, args: &[Rc<Object>]) -> Result<Object, EvalError> {
+    Ok(Object::Bool(
+        str_arg(args, 0)?.starts_with(str_arg(args, 1)?),
+    ))
+}
+
+fn ends_with(_: &mut Interpreter, args: &[Rc<Object>]) -> Result<Object, EvalError> {
+    Ok(Object::Bool(str_arg(args, 0)?.ends_with(str_arg(args, 1)?)))
+}
+
+// An iterator over the pieces between each `sep`.
+fn split(_: &mut Interpreter, args: &[Rc<Object>]) -> Result<Object, EvalError> {
+    let (s, sep) = (str_arg(args, 0)?, str_arg(args, 1)?);
+    if sep.is_empty() {
+        return Err(EvalError::new("Separator can't be empty."));
+    }
+    Ok(items_fn(s.split(sep)))
+}
+
+// Joins everything a `for-in` loop over the first argument would go through.
+fn join(interpreter: &mut Interpreter, args: &[Rc<Object>]) -> Result<Object, EvalError> {
+    let sep = str_arg(args, 1)?.to_string();
+    let items: Vec<_> = interpreter
+        .collect_items(args[0].clone())?
+        .iter()
+        .map(|item| item.to_string())
+        .collect();
+    Ok(Object::Str(items.join(&sep)))
+}
+
+fn trim(_: &mut Interpreter, args: &[Rc<Object>]) -> Result<Object, EvalError> {
+    Ok(Object::Str(str_arg(args, 0)?.trim().to_string()))
+}
+
+fn trim_start(_: &mut Interpreter, args: &[Rc<Object>]) -> Result<Object, EvalError> {
+    Ok(Object::Str(str_arg(args, 0)?.trim_start().to_string()))
+}
+
+fn trim_end(_: &mut Interpreter, args: &[Rc<Object>]) -> Result<Object, EvalError> {
+    Ok(Object::Str(str_arg(args, 0)?.trim_end().to_string()))
+}
+
+fn upper(_: &mut Interpreter, args: &[Rc<Object>]) -> Result<Object, EvalError> {
+    Ok(Object::Str(str_arg(args, 0)?.to_uppercase()))
+}
+
+fn lower(_: &mut Interpreter, args: &[Rc<Object>]) -> Result<Object, EvalError> {
+    Ok(Object::Str(str_arg(args, 0)?.to_lowercase()))
+}
+
+fn replace(_: &mut Interpreter, args: &[Rc<Object>]) -> Result<Object, EvalError> {
+    let (s, from, to) = (str_arg(args, 0)?, str_arg(args, 1)?, str_arg(args, 2)?);
+    if from.is_empty() {
+        return Err(EvalError::new("Can't replace an empty string."));
+    }
+    Ok(Object::Str(s.replace(from, to)))
+}
+
+fn repeat(_: &mut Interpreter, args: &[Rc<Object>]) -> Result<Object, EvalError> {
+    let (s, n) = (str_arg(args, 0)?, int_arg(args, 1)?);
+    if n < 0 {
+        return Err(EvalError::new("Repeat count can't be negative."));
+    }
+    match s.len().checked_mul(n as usize) {
+        Some(len) if len <= MAX_REPEAT_LEN => Ok(Object::Str(s.repeat(n as usize))),
+        _ => Err(EvalError::new("Repeated string is too long.")),
+    }
+}
+
+fn chars(_: &mut Interpreter, args: &[Rc<Object>]) -> Result<Object, EvalError> {
+    Ok(items_fn(str_arg(args, 0)?.chars()))
+}
+
+// The code point of a one-char string.
+fn ord(_: &mut Interpreter, args: &[Rc<Object>]) -> Result<Object, EvalError> {
+    let mut chars = str_arg(args, 0)?.chars();
+    match (chars.next(), chars.next()) {
+        (Some(ch), None) => Ok(Object::Num(ch as u32 as f64)),
+        _ => Err(EvalError::new("Expect a single character.")),
+    }
+}
+
+fn chr(_: &mut Interpreter, args: &[Rc<Object>]) -> Result<Object, EvalError> {
+    let code = int_arg(args, 0)?;
+    u32::try_from(code)
+        .ok()
+        .and_then(char::from_u32)
+        .map(|ch| Object::Str(ch.to_string()))
+        .ok_or_else(|| EvalError::new(&format!("Invalid code point {}.", code)))
+}
@@ -0,0 +1,308 @@
+use crate::builtins::builtins;
+use crate::expr::Expr;
+use crate::stmt::{Pattern, Stmt};
+use crate::token::Token;
+
+use std::collections::HashMap;
+
+pub enum SymbolKind {
+    Variable,
+    Parameter,
+    Function { params: Vec<String> },
+    Builtin { arity: usize },
+}
+
+pub struct Symbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    // Builtins have no declaration in the source.
+    pub decl: Option<Token>,
+}
+
+pub struct Reference {
+    pub token: Token,
+    pub symbol: usize,
+}
+
+// Every declaration and use of a name in a program, linked to the
+// declaration it resolves to.
+pub struct Symbols {
+    pub symbols: Vec<Symbol>,
+    // Declarations are included, so these cover every occurrence.
+    pub references: Vec<Reference>,
+}
+
+impl Symbols {
+    pub fn build(stmts: &[Stmt]) -> Self {
+        let mut builder = SymbolsBuilder {
+            symbols: Vec::new(),
+            references: Vec::new(),
+            scopes: Vec::new(),
+            globals: HashMap::new(),
+            unresolved: Vec::new(),
+        };
+        builder.stmts(stmts);
+        builder.finish()
+    }
+
+    pub fn symbol_at(&self, line: u32, column: u32) -> Option<usize> {
+        self.references
+            .iter()
+            .find(|r| {
+                let start = r.token.column;
+                let end = start + r.token.lexeme.chars().count() as u32;
+                r.token.line == line && start <= column && column <= end
+            })
+            .map(|r| r.symbol)
+    }
+
+    pub fn references_to(&self, symbol: usize) -> impl Iterator<Item = &Token> {
+        self.references
+            .iter()
+            .filter(move |r| r.symbol == symbol)
+            .map(|r| &r.token)
+    }
+}
+
+struct SymbolsBuilder {
+    symbols: Vec<Symbol>,
+    references: Vec<Reference>,
+    // Like the parser's scopes, so that resolved depths can be followed.
+    scopes: Vec<Vec<usize>>,
+    globals: HashMap<String, usize>,
+    // Globals are late bound, so they are resolved once all are declared.
+    unresolved: Vec<Token>,
+}
+
+impl SymbolsBuilder {
+    fn finish(mut self) -> Symbols {
+        let natives: HashMap<_, _> = builtins()
+            .into_iter()
+            .map(|(name, f)| (name, f.arity()))
+            .collect();
+        for token in std::mem::take(&mut self.unresolved) {
+            let symbol = match self.globals.get(&token.lexeme) {
+                Some(symbol) => *symbol,
+                None => match natives.get(token.lexeme.as_str()) {
+                    Some(arity) => {
+                        let kind = SymbolKind::Builtin { arity: *arity };
+                        self.add_symbol(&token.lexeme, kind, None)
+                    }
+                    None => continue,
+                },
+            };
+            self.references.push(Reference { token, symbol });
+        }
+        Symbols {
+            symbols: self.symbols,
+            references: self.references,
+        }
+    }
+
+    fn add_symbol(&mut self, name: &str, kind: SymbolKind, decl: Option<Token>) -> usize {
+        self.symbols.push(Symbol {
+            name: name.to_string(),
+            kind,
+            decl,
+        });
+        let symbol = self.symbols.len() - 1;
+        if self.scopes.is_empty() {
+            self.globals.insert(name.to_string(), symbol);
+        }
+        symbol
+    }
+
+    fn declare(&mut self, name: &Token, kind: SymbolKind) {
+        let symbol = self.add_symbol(&name.lexeme, kind, Some(name.clone()));
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.push(symbol);
+        }
+        self.references.push(Reference {
+            token: name.clone(),
+            symbol,
+        });
+    }
+
+    fn resolve(&mut self, name: &Token, depth: Option<u32>) {
+        let Some(depth) = depth else {
+            self.unresolved.push(name.clone());
+            return;
+        };
+        let Some(i) = self.scopes.len().checked_sub(depth as usize + 1) else {
+            return;
+        };
+        let found = self.scopes[i]
+            .iter()
+            .rev()
+            .find(|s| self.symbols[**s].name == name.lexeme);
+        if let Some(&symbol) = found {
+            self.references.push(Reference {
+                token: name.clone(),
+                symbol,
+            });
+        }
+    }
+
+    fn stmts(&mut self, stmts: &[Stmt]) {
+        for stmt in stmts {
+            self.stmt(stmt);
+        }
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) {
+        use Stmt::*;
+        match stmt {
+            BlankLine | Comment { .. } | Verbatim { .. } => {}
+            Block { stmts } => {
+                self.scopes.push(Vec::new());
+                self.stmts(stmts);
+                self.scopes.pop();
+            }
+            Expr { expr } | Print { expr } | Return { expr, .. } => self.expr(expr),
+            For {
+                init,
+                condition,
+                inc,
+                block,
+            } => {
+                self.scopes.push(Vec::new());
+                if let Some(init) = init {
+                    self.stmt(init);
+                }
+                if let Some(condition) = condition {
+                    self.expr(condition);
+                }
+                if let Some(inc) = inc {
+                    self.expr(inc);
+                }
+                self.stmt(block);
+                self.scopes.pop();
+            }
+            ForIn {
+                variable,
+                iterable,
+                block,
+            } => {
+                self.scopes.push(Vec::new());
+                self.expr(iterable);
+                self.declare(variable, SymbolKind::Variable);
+                self.stmt(block);
+                self.scopes.pop();
+            }
+            Fun {
+                name, params, body, ..
+            } => {
+                let params_names = params.iter().map(|p| p.lexeme.clone()).collect();
+                self.declare(
+                    name,
+                    SymbolKind::Function {
+                        params: params_names,
+                    },
+                );
+                self.scopes.push(Vec::new());
+                for param in params {
+                    self.declare(param, SymbolKind::Parameter);
+                }
+                self.stmts(body);
+                self.scopes.pop();
+            }
+            If {
+                conditionals,
+                else_block,
+            } => {
+                for (condition, block) in conditionals {
+                    self.expr(condition);
+                    self.stmt(block);
+                }
+                if let Some(block) = else_block {
+                    self.stmt(block);
+                }
+            }
+            Import { alias, names, .. } => {
+                for name in alias.iter().chain(names) {
+                    self.declare(name, SymbolKind::Variable);
+                }
+            }
+            Match { value, arms, .. } => {
+                self.expr(value);
+                for arm in arms {
+                    self.scopes.push(Vec::new());
+                    if let Pattern::Binding(name) = &arm.pattern {
+                        self.declare(name, SymbolKind::Variable);
+                    }
+                    if let Some(guard) = &arm.guard {
+                        self.expr(guard);
+                    }
+                    self.stmt(&arm.body);
+                    self.scopes.pop();
+                }
+            }
+            VarDecl { variable, value } => {
+                self.expr(value);
+                if let crate::expr::Expr::Variable { name, .. } = variable {
+                    self.declare(name, SymbolKind::Variable);
+                }
+            }
+            While { condition, block } => {
+                self.expr(condition);
+                self.stmt(block);
+            }
+        }
+    }
+
+    fn expr(&mut self, expr: &Expr) {
+        use Expr::*;
+        match expr {
+            Assign { variable, value }
+            | CompoundAssign {
+                variable, value, ..
+            } => {
+                self.expr(value);
+                self.expr(variable);
+            }
+            Binary { left, right, .. } | Logic { left, right, .. } => {
+                self.expr(left);
+                self.expr(right);
+            }
+            Call { callee, args, .. } => {
+                self.expr(callee);
+                for arg in args.iter() {
+                    self.expr(arg);
+                }
+            }
+            Conditional {
+                condition,
+                then_branch,
+                else_branch,
+            } => {
+                self.expr(condition);
+                self.expr(then_branch);
+                self.expr(else_branch);
+            }
+            Get { object, .. } => self.expr(object),
+            Grouping { expr } => self.expr(expr),
+            Increment { variable, .. } => self.expr(variable),
+            Interpolation { parts } => {
+                for part in parts {
+                    self.expr(part);
+                }
+            }
+            Index { object, index, .. } => {
+                self.expr(object);
+                self.expr(index);
+            }
+            Literal { .. } => {}
+            Range {
+                start, end, step, ..
+            } => {
+                self.expr(start);
+                self.expr(end);
+                if let Some(step) = step {
+                    self.expr(step);
+                }
+            }
+            Unary { right, .. } => self.expr(right),
+            Variable { name, depth } => self.resolve(name, *depth),
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use crate::callable::Callable;
+use crate::interpreter::Interpreter;
+use crate::lox_error::EvalError;
+use crate::object::Object;
+use crate::strings::ItemsFn;
+
+use std::env;
+use std::rc::Rc;
+
+// The natives for talking to the surrounding process, declared in every
+// global environment.
+pub fn system() -> Vec<(&'static str, Rc<dyn Callable>)> {
+    vec![
+        ("arg", Rc::new(ArgFn {})),
+        ("args", Rc::new(ArgsFn {})),
+        ("exit", Rc::new(ExitFn {})),
+        ("getenv", Rc::new(GetenvFn {})),
+    ]
+}
+
+#[derive(Debug)]
+pub struct ExitFn {}
+
+impl Callable for ExitFn {
+    fn arity(&self) -> usize {
+        0
+    }
+
+    // The optional argument is the exit status, 0 by default.
+    fn max_arity(&self) -> usize {
+        1
+    }
+
+    fn name(&self) -> Option<&str> {
+        Some("exit")
+    }
+
+    fn call(
+        &self,
+        _interpreter: &mut Interpreter,
+        args: &[Rc<Object>],
+    ) -> Result<Rc<Object>, EvalError> {
+        match args.first().map(|arg| &**arg) {
+            None => Err(EvalError::Exit(0)),
+            Some(Object::Num(n)) if n.fract() == 0.0 && *n >= 0.0 && *n <= 255.0 => {
+                Err(EvalError::Exit(*n as i32))
+            }
+            Some(_) => Err(EvalError::new(
+                "Exit status must be an integer from 0 to 255.",
+            )),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct GetenvFn {}
+
+impl Callable for GetenvFn {
+    fn arity(&self) -> usize {
+        1
+    }
+
+    fn name(&self) -> Option<&str> {
+        Some("getenv")
+    }
+
+    // Nil when the variable isn't set or isn't valid unicode.
+    fn call(
+        &self,
+        _interpreter: &mut Interpreter,
+        args: &[Rc<Object>],
+    ) -> Result<Rc<Object>, EvalError> {
+        let Object::Str(name) = &*args[0] else {
+            return Err(EvalError::new("Expect string."));
+        };
+        match env::var(name) {
+            Ok(value) => Ok(Rc::new(Object::Str(value))),
+            Err(_) => Ok(Rc::new(Object::Nil)),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ArgsFn {}
+
+impl Callable for ArgsFn {
+    fn arity(&self) -> usize {
+        0
+    }
+
+    fn name(&self) -> Option<&str> {
+        Some("args")
+    }
+
+    // An iterator function over the arguments given after the script path.
+    fn call(
+        &self,
+        interpreter: &mut Interpreter,
+        _args: &[Rc<Object>],
+    ) -> Result<Rc<Object>, EvalError> {
+        let args = interpreter
+            .script_args()
+            .iter()
+            .map(|arg| Rc::new(Object::Str(arg.clone())))
+            .collect();
+        Ok(Rc::new(Object::Fun(Rc::new(ItemsFn::new(args)))))
+    }
+}
+
+#[derive(Debug)]
+pub struct ArgFn {}
+
+impl Callable for ArgFn {
+    fn arity(&self) -> usize {
+        1
+    }
+
+    fn name(&self) -> Option<&str> {
+        Some("arg")
+    }
+
+    // The argument at index `i` after the script path, or nil.
+    fn call(
+        &self,
+        interpreter: &mut Interpreter,
+        args: &[Rc<Object>],
+    ) -> Result<Rc<Object>, EvalError> {
+        let Object::Num(i) = &*args[0] else {
+            return Err(EvalError::new("Index must be an integer."));
+        };
+        if i.fract() != 0.0 {
+            return Err(EvalError::new("Index must be an integer."));
+        }
+        let arg = match usize::try_from(*i as i64) {
+            Ok(i) => interpreter.script_args().get(i).cloned(),
+            Err(_) => None,
+        };
+        match arg {
+            Some(arg) => Ok(Rc::new(Object::Str(arg))),
+            None => Ok(Rc::new(Object::Nil)),
+        }
+    }
+}
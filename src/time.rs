@@ -0,0 +1,341 @@
+use crate::callable::Callable;
+use crate::interpreter::Interpreter;
+use crate::lox_error::EvalError;
+use crate::object::Object;
+
+use std::rc::Rc;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+const MS_PER_DAY: i64 = 86_400_000;
+
+// The time natives declared in every global environment. Timestamps are
+// milliseconds since the Unix epoch, like `clock()`, and always UTC.
+pub fn time() -> Vec<(&'static str, Rc<dyn Callable>)> {
+    vec![
+        ("format_time", Rc::new(FormatTimeFn {})),
+        ("now", Rc::new(NowFn {})),
+        ("parse_time", Rc::new(ParseTimeFn {})),
+        ("sleep", Rc::new(SleepFn {})),
+        ("timer", Rc::new(TimerFn {})),
+    ]
+}
+
+#[derive(Debug)]
+pub struct NowFn {}
+
+impl Callable for NowFn {
+    fn arity(&self) -> usize {
+        0
+    }
+
+    fn name(&self) -> Option<&str> {
+        Some("now")
+    }
+
+    // Unlike `clock()`, keeps the fraction of a millisecond.
+    fn call(
+        &self,
+        _interpreter: &mut Interpreter,
+        _args: &[Rc<Object>],
+    ) -> Result<Rc<Object>, EvalError> {
+        let since_epoch = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        Ok(Rc::new(Object::Num(since_epoch.as_nanos() as f64 / 1e6)))
+    }
+}
+
+#[derive(Debug)]
+pub struct TimerFn {}
+
+impl Callable for TimerFn {
+    fn arity(&self) -> usize {
+        0
+    }
+
+    fn name(&self) -> Option<&str> {
+        Some("timer")
+    }
+
+    // Returns a function giving the milliseconds since the timer started,
+    // measured with a clock that never jumps back.
+    fn call(
+        &self,
+        _interpreter: &mut Interpreter,
+        _args: &[Rc<Object>],
+    ) -> Result<Rc<Object>, EvalError> {
+        let elapsed: Rc<dyn Callable> = Rc::new(ElapsedFn {
+            start: Instant::now(),
+        });
+        Ok(Rc::new(Object::Fun(elapsed)))
+    }
+}
+
+#[derive(Debug)]
+pub struct ElapsedFn {
+    start: Instant,
+}
+
+impl Callable for ElapsedFn {
+    fn arity(&self) -> usize {
+        0
+    }
+
+    fn call(
+        &self,
+        _interpreter: &mut Interpreter,
+        _args: &[Rc<Object>],
+    ) -> Result<Rc<Object>, EvalError> {
+        let elapsed = self.start.elapsed().as_nanos() as f64 / 1e6;
+        Ok(Rc::new(Object::Num(elapsed)))
+    }
+}
+
+#[derive(Debug)]
+pub struct SleepFn {}
+
+impl Callable for SleepFn {
+    fn arity(&self) -> usize {
+        1
+    }
+
+    fn name(&self) -> Option<&str> {
+        Some("sleep")
+    }
+
+    fn call(
+        &self,
+        _interpreter: &mut Interpreter,
+        args: &[Rc<Object>],
+    ) -> Result<Rc<Object>, EvalError> {
+        let ms = match &*args[0] {
+            Object::Num(ms) if *ms >= 0.0 => ms,
+            _ => {
+                return Err(EvalError::new(
+                    "Expect a non-negative number of milliseconds.",
+                ))
+            }
+        };
+        let Ok(duration) = Duration::try_from_secs_f64(ms / 1000.0) else {
+            return Err(EvalError::new("Sleep duration is too long."));
+        };
+        thread::sleep(duration);
+        Ok(Rc::new(Object::Nil))
+    }
+}
+
+#[derive(Debug)]
+pub struct FormatTimeFn {}
+
+impl Callable for FormatTimeFn {
+    fn arity(&self) -> usize {
+        2
+    }
+
+    fn name(&self) -> Option<&str> {
+        Some("format_time")
+    }
+
+    fn call(
+        &self,
+        _interpreter: &mut Interpreter,
+        args: &[Rc<Object>],
+    ) -> Result<Rc<Object>, EvalError> {
+        let (Object::Num(ts), Object::Str(format)) = (&*args[0], &*args[1]) else {
+            return Err(EvalError::new("Expect a timestamp and a format string."));
+        };
+        if !ts.is_finite() {
+            return Err(EvalError::new("Timestamp must be finite."));
+        }
+        Ok(Rc::new(Object::Str(format_time(*ts as i64, format)?)))
+    }
+}
+
+#[derive(Debug)]
+pub struct ParseTimeFn {}
+
+impl Callable for ParseTimeFn {
+    fn arity(&self) -> usize {
+        2
+    }
+
+    fn name(&self) -> Option<&str> {
+        Some("parse_time")
+    }
+
+    fn call(
+        &self,
+        _interpreter: &mut Interpreter,
+        args: &[Rc<Object>],
+    ) -> Result<Rc<Object>, EvalError> {
+        let (Object::Str(text), Object::Str(format)) = (&*args[0], &*args[1]) else {
+            return Err(EvalError::new("Expect a time string and a format string."));
+        };
+        Ok(Rc::new(Object::Num(parse_time(text, format)? as f64)))
+    }
+}
+
+// The fields of a UTC timestamp.
+struct DateTime {
+    year: i64,
+    month: i64,
+    day: i64,
+    hour: i64,
+    minute: i64,
+    second: i64,
+    milli: i64,
+}
+
+impl DateTime {
+    fn from_timestamp(ts: i64) -> Self {
+        let (days, ms) = (ts.div_euclid(MS_PER_DAY), ts.rem_euclid(MS_PER_DAY));
+        let (year, month, day) = civil_from_days(days);
+        DateTime {
+            year,
+            month,
+            day,
+            hour: ms / 3_600_000,
+            minute: ms / 60_000 % 60,
+            second: ms / 1000 % 60,
+            milli: ms % 1000,
+        }
+    }
+
+    fn to_timestamp(&self) -> i64 {
+        days_from_civil(self.year, self.month, self.day) * MS_PER_DAY
+            + self.hour * 3_600_000
+            + self.minute * 60_000
+            + self.second * 1000
+            + self.milli
+    }
+}
+
+// Supports %Y, %m, %d, %H, %M, %S, %f (milliseconds) and %%.
+fn format_time(ts: i64, format: &str) -> Result<String, EvalError> {
+    let time = DateTime::from_timestamp(ts);
+    let mut out = String::new();
+    let mut chars = format.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '%' {
+            out.push(ch);
+            continue;
+        }
+        match chars.next() {
+            Some('Y') => out.push_str(&format!("{:04}", time.year)),
+            Some('m') => out.push_str(&format!("{:02}", time.month)),
+            Some('d') => out.push_str(&format!("{:02}", time.day)),
+            Some('H') => out.push_str(&format!("{:02}", time.hour)),
+            Some('M') => out.push_str(&format!("{:02}", time.minute)),
+            Some('S') => out.push_str(&format!("{:02}", time.second)),
+            Some('f') => out.push_str(&format!("{:03}", time.milli)),
+            Some('%') => out.push('%'),
+            spec => return Err(unknown_specifier(spec)),
+        }
+    }
+    Ok(out)
+}
+
+// The inverse of `format_time`, for the same specifiers.
+fn parse_time(text: &str, format: &str) -> Result<i64, EvalError> {
+    let mut time = DateTime::from_timestamp(0);
+    let text: Vec<char> = text.chars().collect();
+    let mut pos = 0;
+    let mut chars = format.chars();
+    while let Some(ch) = chars.next() {
+        let (field, width, range) = match ch {
+            '%' => match chars.next() {
+                Some('Y') => (&mut time.year, 4, 0..=9999),
+                Some('m') => (&mut time.month, 2, 1..=12),
+                Some('d') => (&mut time.day, 2, 1..=31),
+                Some('H') => (&mut time.hour, 2, 0..=23),
+                Some('M') => (&mut time.minute, 2, 0..=59),
+                Some('S') => (&mut time.second, 2, 0..=59),
+                Some('f') => (&mut time.milli, 3, 0..=999),
+                Some('%') => {
+                    expect_char(&text, &mut pos, '%')?;
+                    continue;
+                }
+                spec => return Err(unknown_specifier(spec)),
+            },
+            ch => {
+                expect_char(&text, &mut pos, ch)?;
+                continue;
+            }
+        };
+        let digits: String = text.iter().skip(pos).take(width).collect();
+        match digits.parse::<i64>() {
+            Ok(n) if digits.len() == width && digits.chars().all(|c| c.is_ascii_digit()) => {
+                if !range.contains(&n) {
+                    return Err(EvalError::new(&format!(
+                        "Time field {} out of range at position {}.",
+                        digits, pos
+                    )));
+                }
+                *field = n;
+            }
+            _ => {
+                return Err(EvalError::new(&format!(
+                    "Expect {} digits at position {}.",
+                    width, pos
+                )))
+            }
+        }
+        pos += width;
+    }
+    if pos < text.len() {
+        return Err(EvalError::new(&format!(
+            "Unexpected text at position {}.",
+            pos
+        )));
+    }
+    if time.day > days_in_month(time.year, time.month) {
+        return Err(EvalError::new("Day out of range for month."));
+    }
+    Ok(time.to_timestamp())
+}
+
+fn expect_char(text: &[char], pos: &mut usize, ch: char) -> Result<(), EvalError> {
+    if text.get(*pos) != Some(&ch) {
+        return Err(EvalError::new(&format!(
+            "Expect '{}' at position {}.",
+            ch, pos
+        )));
+    }
+    *pos += 1;
+    Ok(())
+}
+
+fn unknown_specifier(spec: Option<char>) -> EvalError {
+    match spec {
+        Some(spec) => EvalError::new(&format!("Unknown time format '%{}'.", spec)),
+        None => EvalError::new("Time format can't end with '%'."),
+    }
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    days_from_civil(year + month / 12, month % 12 + 1, 1) - days_from_civil(year, month, 1)
+}
+
+// Days since 1970-01-01 in the proleptic Gregorian calendar, following
+// Howard Hinnant's `days_from_civil`.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
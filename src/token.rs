@@ -6,6 +6,8 @@ pub struct Token {
     pub lexeme: String,
     pub literal: String,
     pub line: u32,
+    // Zero-based, counted in chars from the start of the token's first line.
+    pub column: u32,
 }
 
 impl Token {
@@ -15,6 +17,7 @@ impl Token {
             lexeme,
             literal,
             line,
+            column: 0,
         }
     }
 }
@@ -32,38 +35,68 @@ pub enum TokenType {
     RightParen,
     LeftBrace,
     RightBrace,
+    LeftBracket,
+    RightBracket,
+    Ampersand,
+    Caret,
+    Colon,
     Comma,
     Dot,
     Minus,
+    Percent,
+    Pipe,
     Plus,
+    Question,
     Semicolon,
     Slash,
     Star,
+    Tilde,
 
     // one or two character tokens.,
     Bang,
     BangEqual,
+    DotDot,
+    DotDotEqual,
     Equal,
     EqualEqual,
+    EqualGreater,
     Greater,
     GreaterEqual,
+    GreaterGreater,
     Less,
     LessEqual,
+    LessLess,
+    MinusEqual,
+    MinusMinus,
+    PercentEqual,
+    PlusEqual,
+    PlusPlus,
+    SlashEqual,
+    StarEqual,
+    StarStar,
+    TildeSlash,
 
     // literals.,
     Identifier(String),
     StringLit(String),
+    // A string segment that is followed by an interpolated `${expr}`.
+    StringInterp(String),
     Number(f64),
 
     // keywords.,
     And,
+    As,
     Class,
     Elif,
     Else,
     False,
     Fun,
     For,
+    From,
     If,
+    Import,
+    In,
+    Match,
     Nil,
     Or,
     Print,
@@ -74,6 +107,9 @@ pub enum TokenType {
     Var,
     While,
 
+    // trivia, only kept when formatting.
+    Comment(String),
+
     Eof,
     Sof,
 }